@@ -2,6 +2,8 @@ codegen_ssa_L4Bender_exporting_symbols_unimplemented = exporting symbols not imp
 
 codegen_ssa_add_native_library = failed to add native library {$library_path}: {$error}
 
+codegen_ssa_apple_linked_as_i386 = linking `i686` code with `-arch i386`, as the linker does not distinguish the two
+
 codegen_ssa_apple_sdk_error_sdk_path = failed to get {$sdk_name} SDK path: {$error}
 
 codegen_ssa_archive_build_failure = failed to build archive at `{$path}`: {$error}
@@ -195,6 +197,9 @@ codegen_ssa_multiple_external_func_decl = multiple declarations of external func
 codegen_ssa_multiple_main_functions = entry symbol `main` declared multiple times
     .help = did you use `#[no_mangle]` on `fn main`? Use `#[start]` instead
 
+codegen_ssa_newer_apple_deployment_target = dependency `{$crate_name}` was compiled for deployment target {$version}, which is newer than the current deployment target {$current}
+    .note = the output may use APIs that are not available on all OS versions it is meant to support
+
 codegen_ssa_no_field = no field `{$name}`
 
 codegen_ssa_no_module_named =
@@ -204,11 +209,20 @@ codegen_ssa_no_natvis_directory = error enumerating natvis directory: {$error}
 
 codegen_ssa_no_saved_object_file = cached cgu {$cgu_name} should have an object file, but doesn't
 
+codegen_ssa_old_apple_sdk = the SDK at `{$sdk_root}` is version {$version}, which is older than the version {$required} that the output is linked for
+    .note = linking may fail, or the output may use APIs that the SDK does not declare
+    .help = use a newer SDK, or lower the deployment target
+
 codegen_ssa_processing_dymutil_failed = processing debug info with `dsymutil` failed: {$status}
     .note = {$output}
 
 codegen_ssa_read_file = failed to read file: {$message}
 
+codegen_ssa_removed_deployment_target = removed `{$var}={$value}` from the linker environment, because it does not apply to the current target
+
+codegen_ssa_removed_sdkroot = removed `SDKROOT={$sdkroot}` from the linker environment, because it targets {$platform} while building for macOS
+    .help = set `RUSTC_KEEP_SDKROOT=1` to keep it
+
 codegen_ssa_repair_vs_build_tools = the Visual Studio build tools may need to be repaired using the Visual Studio installer
 
 codegen_ssa_rlib_archive_build_failure = failed to build archive from rlib at `{$path}`: {$error}
@@ -38,9 +38,11 @@
 use rustc_span::symbol::Symbol;
 use rustc_target::spec::crt_objects::CrtObjects;
 use rustc_target::spec::{
-    Cc, LinkOutputKind, LinkSelfContainedComponents, LinkSelfContainedDefault, LinkerFeatures,
-    LinkerFlavor, LinkerFlavorCli, Lld, PanicStrategy, RelocModel, RelroLevel, SanitizerSet,
-    SplitDebuginfo,
+    apple_deployment_target_env_vars, apple_sdk_root_version, apple_sdkroot_other_platform,
+    current_apple_deployment_target, current_apple_ld_arch_is_downgraded,
+    current_apple_linked_platform_version, Cc, LinkOutputKind, LinkSelfContainedComponents,
+    LinkSelfContainedDefault, LinkerFeatures, LinkerFlavor, LinkerFlavorCli, Lld, PanicStrategy,
+    RelocModel, RelroLevel, SanitizerSet, SplitDebuginfo,
 };
 use tempfile::Builder as TempFileBuilder;
 use tracing::{debug, info, warn};
@@ -750,6 +752,60 @@ fn read_input(&self, path: &Path) -> std::io::Result<&[u8]> {
     }
 }
 
+/// Warns about rlibs that were built for a newer deployment target than the current one, as the
+/// output could then use APIs that are unavailable on the oldest OS version it claims to support.
+///
+/// The deployment target of a dependency is the one recorded in its crate metadata, see the
+/// `apple_deployment_target` query.
+fn warn_newer_apple_deployment_targets(sess: &Session, codegen_results: &CodegenResults) {
+    let Ok(current) = current_apple_deployment_target(&sess.target) else {
+        return;
+    };
+
+    let crate_info = &codegen_results.crate_info;
+    for cnum in &crate_info.used_crates {
+        // Only rlibs end up in the output itself.
+        if crate_info.used_crate_source[cnum].rlib.is_none() {
+            continue;
+        }
+        if let Some(&version) = crate_info.apple_deployment_targets.get(cnum)
+            && version > current
+        {
+            sess.dcx().emit_warn(errors::NewerAppleDeploymentTarget {
+                crate_name: crate_info.crate_name[cnum],
+                version: fmt_apple_version(version),
+                current: fmt_apple_version(current),
+            });
+        }
+    }
+}
+
+/// Warns if the SDK at `sdk_root` is older than the deployment target or the SDK version that the
+/// output is linked for, as the linker may then fail in confusing ways. This does nothing if the
+/// version of the SDK cannot be detected.
+fn warn_old_apple_sdk(sess: &Session, sdk_root: &Path) {
+    let Some((min_version, sdk_version)) = current_apple_linked_platform_version(&sess.target)
+    else {
+        return;
+    };
+    let Some(version) = apple_sdk_root_version(sdk_root) else {
+        return;
+    };
+    let required = min_version.max(sdk_version);
+    if version < required {
+        sess.dcx().emit_warn(errors::OldAppleSdk {
+            sdk_root: sdk_root.to_path_buf(),
+            version: fmt_apple_version(version),
+            required: fmt_apple_version(required),
+        });
+    }
+}
+
+/// Formats an Apple OS version for diagnostics, leaving out a zero patch version.
+fn fmt_apple_version((major, minor, patch): (u16, u8, u8)) -> String {
+    if patch == 0 { format!("{major}.{minor}") } else { format!("{major}.{minor}.{patch}") }
+}
+
 /// Create a dynamic library or executable.
 ///
 /// This will invoke the system linker/cc to create the resulting file. This links to all upstream
@@ -785,6 +841,29 @@ fn link_natively(
     for k in sess.target.link_env_remove.as_ref() {
         cmd.env_remove(k.as_ref());
     }
+    if sess.opts.verbose
+        && sess.target.link_env_remove.iter().any(|k| k == "SDKROOT")
+        && let Ok(sdkroot) = env::var("SDKROOT")
+        && let Some(platform) = apple_sdkroot_other_platform(&sdkroot)
+    {
+        sess.dcx().emit_note(errors::RemovedSdkroot { sdkroot, platform });
+    }
+    if sess.opts.verbose {
+        for var in sess.target.link_env_remove.iter() {
+            if apple_deployment_target_env_vars().contains(&&**var)
+                && let Ok(value) = env::var(&**var)
+            {
+                sess.dcx()
+                    .emit_note(errors::RemovedDeploymentTarget { var: var.to_string(), value });
+            }
+        }
+        if current_apple_ld_arch_is_downgraded(&sess.target) {
+            sess.dcx().emit_note(errors::AppleLinkedAsI386);
+        }
+    }
+    if sess.target.is_like_osx {
+        warn_newer_apple_deployment_targets(sess, codegen_results);
+    }
 
     for print in &sess.opts.prints {
         if print.kind == PrintKind::LinkArgs {
@@ -2976,6 +3055,12 @@ fn add_apple_sdk(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) ->
     }
 
     if os == "macos" && !matches!(flavor, LinkerFlavor::Darwin(Cc::No, _)) {
+        // `cc` finds the SDK itself, but check the one it is pointed to.
+        if let Ok(sdk_root) = env::var("SDKROOT")
+            && apple_sdkroot_other_platform(&sdk_root).is_none()
+        {
+            warn_old_apple_sdk(sess, Path::new(&sdk_root));
+        }
         return None;
     }
 
@@ -3010,6 +3095,7 @@ fn add_apple_sdk(cmd: &mut dyn Linker, sess: &Session, flavor: LinkerFlavor) ->
             return None;
         }
     };
+    warn_old_apple_sdk(sess, Path::new(&sdk_root));
 
     match flavor {
         LinkerFlavor::Darwin(Cc::Yes, _) => {
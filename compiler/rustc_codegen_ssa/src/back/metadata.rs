@@ -19,7 +19,7 @@
 use rustc_session::Session;
 use rustc_span::sym;
 use rustc_target::abi::Endian;
-use rustc_target::spec::{ef_avr_arch, RelocModel, Target};
+use rustc_target::spec::{apple_arch_from_rust_triple, ef_avr_arch, AppleArch, RelocModel, Target};
 
 /// The default metadata loader. This is used by cg_llvm and cg_clif.
 ///
@@ -237,7 +237,7 @@ pub(crate) fn create_object_file(sess: &Session) -> Option<write::Object<'static
             file.set_macho_cpu_subtype(object::macho::CPU_SUBTYPE_ARM64E);
         }
 
-        file.set_macho_build_version(macho_object_build_version_for_target(&sess.target))
+        file.set_macho_build_version(macho_object_build_version_for_target(sess))
     }
     if binary_format == BinaryFormat::Coff {
         // Disable the default mangler to avoid mangling the special "@feat.00" symbol name.
@@ -390,7 +390,7 @@ pub(crate) fn create_object_file(sess: &Session) -> Option<write::Object<'static
 ///
 /// Since Xcode 15, Apple's LD apparently requires object files to use this load command, so this
 /// returns the `MachOBuildVersion` for the target to do so.
-fn macho_object_build_version_for_target(target: &Target) -> object::write::MachOBuildVersion {
+fn macho_object_build_version_for_target(sess: &Session) -> object::write::MachOBuildVersion {
     /// The `object` crate demands "X.Y.Z encoded in nibbles as xxxx.yy.zz"
     /// e.g. minOS 14.0 = 0x000E0000, or SDK 16.2 = 0x00100200
     fn pack_version((major, minor, patch): (u16, u8, u8)) -> u32 {
@@ -398,22 +398,46 @@ fn pack_version((major, minor, patch): (u16, u8, u8)) -> u32 {
         (major << 16) | (minor << 8) | patch
     }
 
+    let target = &sess.target;
     let platform =
         rustc_target::spec::current_apple_platform(target).expect("unknown Apple target OS");
-    let min_os = rustc_target::spec::current_apple_deployment_target(target);
-    let (sdk_major, sdk_minor) =
-        rustc_target::spec::current_apple_sdk_version(platform).expect("unknown Apple target OS");
+    let min_os = rustc_target::spec::current_apple_deployment_target(target)
+        .unwrap_or_else(|err| sess.dcx().fatal(err.to_string()));
+
+    // With `-Zapple-reproducible`, record the SDK version that was asked for
+    // (it was validated in `build_target_config`).
+    // With `-Zapple-strict-sdk`, record the version of the SDK that was found
+    // there instead, if the target passes `-platform_version` to the linker
+    // where it could be pinned (custom targets may not). Otherwise, this
+    // doesn't depend on the build machine.
+    let sdk = match &sess.opts.unstable_opts.apple_reproducible {
+        Some(sdk) => rustc_target::spec::parse_apple_sdk_version(sdk)
+            .unwrap_or_else(|err| sess.dcx().fatal(err.to_string())),
+        None => sess
+            .opts
+            .unstable_opts
+            .apple_strict_sdk
+            .then(|| rustc_target::spec::current_apple_linked_platform_version(target))
+            .flatten()
+            .map(|(_, sdk)| sdk)
+            .unwrap_or_else(|| {
+                rustc_target::spec::apple_object_sdk_version(platform, min_os)
+                    .expect("unknown Apple target OS")
+            }),
+    };
 
     let mut build_version = object::write::MachOBuildVersion::default();
     build_version.platform = platform;
     build_version.minos = pack_version(min_os);
-    build_version.sdk = pack_version((sdk_major, sdk_minor, 0));
+    // The SDK version is never lower than the deployment target, even if the
+    // user requested a newer deployment target than the SDK version we know of.
+    build_version.sdk = pack_version(sdk.max(min_os));
     build_version
 }
 
 /// Is Apple's CPU subtype `arm64e`s
 fn macho_is_arm64e(target: &Target) -> bool {
-    target.llvm_target.starts_with("arm64e")
+    apple_arch_from_rust_triple(&target.llvm_target) == Some(AppleArch::Arm64e)
 }
 
 pub enum MetadataPosition {
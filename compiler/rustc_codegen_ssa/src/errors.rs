@@ -538,6 +538,44 @@ pub struct UnsupportedArch<'a> {
     pub os: &'a str,
 }
 
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_removed_deployment_target)]
+pub struct RemovedDeploymentTarget {
+    pub var: String,
+    pub value: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_apple_linked_as_i386)]
+pub struct AppleLinkedAsI386;
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_removed_sdkroot)]
+#[help]
+pub struct RemovedSdkroot {
+    pub sdkroot: String,
+    pub platform: &'static str,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_newer_apple_deployment_target)]
+#[note]
+pub struct NewerAppleDeploymentTarget {
+    pub crate_name: Symbol,
+    pub version: String,
+    pub current: String,
+}
+
+#[derive(Diagnostic)]
+#[diag(codegen_ssa_old_apple_sdk)]
+#[note]
+#[help]
+pub struct OldAppleSdk {
+    pub sdk_root: PathBuf,
+    pub version: String,
+    pub required: String,
+}
+
 #[derive(Diagnostic)]
 pub enum AppleSdkRootError<'a> {
     #[diag(codegen_ssa_apple_sdk_error_sdk_path)]
@@ -861,7 +861,11 @@ fn print_crate_info(
                 use rustc_target::spec::current_apple_deployment_target;
 
                 if sess.target.is_like_osx {
-                    let (major, minor, patch) = current_apple_deployment_target(&sess.target);
+                    let (major, minor, patch) = current_apple_deployment_target(&sess.target)
+                        .unwrap_or_else(|err| {
+                            #[allow(rustc::diagnostic_outside_of_impl)]
+                            sess.dcx().fatal(err.to_string())
+                        });
                     let patch = if patch != 0 { format!(".{patch}") } else { String::new() };
                     println_info!("deployment_target={major}.{minor}{patch}")
                 } else {
@@ -869,6 +873,53 @@ fn print_crate_info(
                     sess.dcx().fatal("only Apple targets currently support deployment version info")
                 }
             }
+            DeploymentTargetMin => {
+                use rustc_target::spec::current_apple_min_deployment_target;
+
+                if sess.target.is_like_osx {
+                    let (major, minor, patch) = current_apple_min_deployment_target(&sess.target)
+                        .unwrap_or_else(|err| {
+                            #[allow(rustc::diagnostic_outside_of_impl)]
+                            sess.dcx().fatal(err.to_string())
+                        });
+                    println_info!("deployment_target_min={major}.{minor}.{patch}")
+                } else {
+                    #[allow(rustc::diagnostic_outside_of_impl)]
+                    sess.dcx().fatal("only Apple targets currently support deployment version info")
+                }
+            }
+            ApplePlatform => {
+                use rustc_target::spec::{apple_platform_constant_name, current_apple_platform};
+
+                if let Some(platform) = current_apple_platform(&sess.target) {
+                    let name = apple_platform_constant_name(platform).unwrap_or("unknown");
+                    println_info!("{platform} ({name})")
+                } else {
+                    #[allow(rustc::diagnostic_outside_of_impl)]
+                    sess.dcx().fatal("only Apple targets have a Mach-O platform")
+                }
+            }
+            AppleLlvmTarget => {
+                if sess.target.is_like_osx {
+                    // The deployment target from the environment (or from
+                    // `-Zapple-deployment-target`) is already part of it.
+                    println_info!("{}", sess.target.llvm_target)
+                } else {
+                    #[allow(rustc::diagnostic_outside_of_impl)]
+                    sess.dcx()
+                        .fatal("only Apple targets currently support printing the LLVM target")
+                }
+            }
+            AppleSdkPlatformPathHint => {
+                use rustc_target::spec::current_apple_sdk_platform_name;
+
+                if let Some(name) = current_apple_sdk_platform_name(&sess.target) {
+                    println_info!("sdk_platform={name}")
+                } else {
+                    #[allow(rustc::diagnostic_outside_of_impl)]
+                    sess.dcx().fatal("only Apple targets have an SDK platform")
+                }
+            }
         }
 
         req.out.overwrite(&crate_info, sess);
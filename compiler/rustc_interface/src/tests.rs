@@ -755,6 +755,19 @@ macro_rules! tracked {
     // tidy-alphabetical-start
     tracked!(allow_features, Some(vec![String::from("lang_items")]));
     tracked!(always_encode_mir, true);
+    tracked!(apple_application_extension, true);
+    tracked!(apple_dead_strip_dylibs, true);
+    tracked!(apple_bitcode_marker, true);
+    tracked!(apple_cc_target, true);
+    tracked!(apple_cc_version_min, true);
+    tracked!(apple_deployment_target, Some(String::from("14.0")));
+    tracked!(apple_keep_ar_date, true);
+    tracked!(apple_legacy_version_min, true);
+    tracked!(apple_no_adhoc_codesign, true);
+    tracked!(apple_reproducible, Some(String::from("14.2")));
+    tracked!(apple_strict_sdk, true);
+    tracked!(apple_zero_sdk_version, true);
+    tracked!(apple_zippered, true);
     tracked!(assume_incomplete_release, true);
     tracked!(binary_dep_depinfo, true);
     tracked!(box_noalias, false);
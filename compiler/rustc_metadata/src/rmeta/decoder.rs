@@ -773,6 +773,9 @@ pub(crate) fn list_crate_metadata(
                     writeln!(out, "triple {}", root.header.triple.triple())?;
                     writeln!(out, "edition {}", root.edition)?;
                     writeln!(out, "symbol_mangling_version {:?}", root.symbol_mangling_version)?;
+                    if let Some((major, minor, patch)) = root.apple_deployment_target {
+                        writeln!(out, "apple_deployment_target {major}.{minor}.{patch}")?;
+                    }
                     writeln!(
                         out,
                         "required_panic_strategy {:?} panic_in_drop_strategy {:?}",
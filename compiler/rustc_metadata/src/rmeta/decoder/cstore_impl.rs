@@ -21,6 +21,7 @@
 use rustc_span::hygiene::ExpnId;
 use rustc_span::symbol::{kw, Symbol};
 use rustc_span::Span;
+use rustc_target::spec::current_apple_deployment_target;
 
 use super::{Decodable, DecodeContext, DecodeIterator};
 use crate::creader::{CStore, LoadedMacro};
@@ -365,6 +366,7 @@ fn into_args(self) -> (DefId, SimplifiedType) {
     extern_crate => { cdata.extern_crate.map(|c| &*tcx.arena.alloc(c)) }
     is_no_builtins => { cdata.root.no_builtins }
     symbol_mangling_version => { cdata.root.symbol_mangling_version }
+    apple_deployment_target => { cdata.root.apple_deployment_target }
     specialization_enabled_in => { cdata.root.specialization_enabled_in }
     reachable_non_generics => {
         let reachable_non_generics = tcx
@@ -443,6 +445,16 @@ pub(in crate::rmeta) fn provide(providers: &mut Providers) {
         allocator_kind: |tcx, ()| CStore::from_tcx(tcx).allocator_kind(),
         alloc_error_handler_kind: |tcx, ()| CStore::from_tcx(tcx).alloc_error_handler_kind(),
         is_private_dep: |_tcx, LocalCrate| false,
+        apple_deployment_target: |tcx, LocalCrate| {
+            let target = &tcx.sess.target;
+            if !target.is_like_osx {
+                return None;
+            }
+            Some(
+                current_apple_deployment_target(target)
+                    .unwrap_or_else(|err| tcx.dcx().fatal(err.to_string())),
+            )
+        },
         native_library: |tcx, id| {
             tcx.native_libraries(id.krate)
                 .iter()
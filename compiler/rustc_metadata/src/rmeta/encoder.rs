@@ -720,6 +720,7 @@ macro_rules! stat {
                 panic_runtime: attr::contains_name(attrs, sym::panic_runtime),
                 profiler_runtime: attr::contains_name(attrs, sym::profiler_runtime),
                 symbol_mangling_version: tcx.sess.opts.get_symbol_mangling_version(),
+                apple_deployment_target: tcx.apple_deployment_target(LOCAL_CRATE),
 
                 crate_deps,
                 dylib_dependency_formats,
@@ -291,6 +291,9 @@ pub(crate) struct CrateRoot {
     panic_runtime: bool,
     profiler_runtime: bool,
     symbol_mangling_version: SymbolManglingVersion,
+    /// The deployment target of crates for Apple targets, so that dependent
+    /// crates don't have to re-derive it.
+    apple_deployment_target: Option<(u16, u8, u8)>,
 
     specialization_enabled_in: bool,
 }
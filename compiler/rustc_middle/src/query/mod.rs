@@ -1519,6 +1519,13 @@
         desc { "getting a crate's symbol mangling version" }
         separate_provide_extern
     }
+    /// The deployment target that a crate for an Apple target was compiled
+    /// with, as a `(major, minor, patch)` version. `None` for other targets.
+    query apple_deployment_target(_: CrateNum) -> Option<(u16, u8, u8)> {
+        fatal_cycle
+        desc { "getting a crate's Apple deployment target" }
+        separate_provide_extern
+    }
 
     query extern_crate(def_id: CrateNum) -> Option<&'tcx ExternCrate> {
         eval_always
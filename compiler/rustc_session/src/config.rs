@@ -26,7 +26,12 @@
     sym, FileName, FileNameDisplayPreference, RealFileName, SourceFileHashAlgorithm, Symbol,
 };
 use rustc_target::spec::{
-    FramePointer, LinkSelfContainedComponents, LinkerFeatures, SplitDebuginfo, Target, TargetTriple,
+    check_apple_deployment_target_file, dead_strip_apple_dylibs, disable_apple_adhoc_codesign,
+    emit_apple_bitcode_marker, keep_apple_ar_date, link_apple_target_as_application_extension,
+    make_apple_target_reproducible, override_apple_deployment_target, require_apple_sdk_version,
+    use_apple_cc_target, use_apple_cc_version_min, use_apple_legacy_version_min,
+    zero_apple_sdk_version, zipper_apple_target, FramePointer, LinkSelfContainedComponents,
+    LinkerFeatures, SplitDebuginfo, Target, TargetTriple,
 };
 use tracing::debug;
 
@@ -832,6 +837,10 @@ pub enum PrintKind {
     LinkArgs,
     SplitDebuginfo,
     DeploymentTarget,
+    DeploymentTargetMin,
+    ApplePlatform,
+    AppleLlvmTarget,
+    AppleSdkPlatformPathHint,
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq)]
@@ -1340,11 +1349,148 @@ pub fn build_configuration(sess: &Session, mut user_cfg: Cfg) -> Cfg {
 
 pub fn build_target_config(early_dcx: &EarlyDiagCtxt, opts: &Options, sysroot: &Path) -> Target {
     match Target::search(&opts.target_triple, sysroot) {
-        Ok((target, warnings)) => {
+        Ok((mut target, warnings)) => {
             for warning in warnings.warning_messages() {
                 early_dcx.early_warn(warning)
             }
 
+            if target.is_like_osx {
+                if let Err(err) = check_apple_deployment_target_file() {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if let Some(version) = &opts.unstable_opts.apple_deployment_target {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-deployment-target` is only supported on Apple targets",
+                    );
+                }
+                if let Err(err) = override_apple_deployment_target(&mut target, version) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_application_extension {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-application-extension` is only supported on Apple targets",
+                    );
+                }
+                link_apple_target_as_application_extension(&mut target);
+            }
+            if opts.unstable_opts.apple_no_adhoc_codesign {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-no-adhoc-codesign` is only supported on Apple targets",
+                    );
+                }
+                disable_apple_adhoc_codesign(&mut target);
+            }
+            if opts.unstable_opts.apple_dead_strip_dylibs {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-dead-strip-dylibs` is only supported on Apple targets",
+                    );
+                }
+                dead_strip_apple_dylibs(&mut target);
+            }
+            if opts.unstable_opts.apple_bitcode_marker {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-bitcode-marker` is only supported on Apple targets",
+                    );
+                }
+                emit_apple_bitcode_marker(&mut target);
+            }
+            if opts.unstable_opts.apple_zippered {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal("`-Z apple-zippered` is only supported on Apple targets");
+                }
+                if let Err(err) = zipper_apple_target(&mut target) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_zero_sdk_version {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-zero-sdk-version` is only supported on Apple targets",
+                    );
+                }
+                zero_apple_sdk_version(&mut target);
+            }
+            if opts.unstable_opts.apple_cc_target {
+                if !target.is_like_osx {
+                    early_dcx
+                        .early_fatal("`-Z apple-cc-target` is only supported on Apple targets");
+                }
+                if let Err(err) = use_apple_cc_target(&mut target) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_cc_version_min {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-cc-version-min` is only supported on Apple targets",
+                    );
+                }
+                if opts.unstable_opts.apple_cc_target {
+                    early_dcx.early_fatal(
+                        "`-Z apple-cc-target` and `-Z apple-cc-version-min` cannot be combined",
+                    );
+                }
+                if let Err(err) = use_apple_cc_version_min(&mut target) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_legacy_version_min {
+                if !target.is_like_osx {
+                    early_dcx.early_fatal(
+                        "`-Z apple-legacy-version-min` is only supported on Apple targets",
+                    );
+                }
+                if let Err(err) = use_apple_legacy_version_min(&mut target) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_keep_ar_date {
+                if !target.is_like_osx {
+                    early_dcx
+                        .early_fatal("`-Z apple-keep-ar-date` is only supported on Apple targets");
+                }
+                keep_apple_ar_date(&mut target);
+            }
+            if let Some(sdk_version) = &opts.unstable_opts.apple_reproducible {
+                if !target.is_like_osx {
+                    early_dcx
+                        .early_fatal("`-Z apple-reproducible` is only supported on Apple targets");
+                }
+                // The `native` deployment target depends on the build machine.
+                let explicit_deployment_target = opts
+                    .unstable_opts
+                    .apple_deployment_target
+                    .as_deref()
+                    .is_some_and(|v| v != "native");
+                if let Err(err) = make_apple_target_reproducible(
+                    &mut target,
+                    sdk_version,
+                    explicit_deployment_target,
+                ) {
+                    early_dcx.early_fatal(err.to_string());
+                }
+            }
+            if opts.unstable_opts.apple_strict_sdk {
+                if !target.is_like_osx {
+                    early_dcx
+                        .early_fatal("`-Z apple-strict-sdk` is only supported on Apple targets");
+                }
+                // `-Z apple-reproducible` already pinned the SDK version.
+                if opts.unstable_opts.apple_reproducible.is_none() {
+                    let sdk_root = std::env::var("SDKROOT").ok();
+                    if let Err(err) = require_apple_sdk_version(&mut target, sdk_root.as_deref()) {
+                        early_dcx.early_fatal(err.to_string());
+                    }
+                }
+            }
+
             // The `wasm32-wasi` target is being renamed to `wasm32-wasip1` as
             // part of rust-lang/compiler-team#607 and
             // rust-lang/compiler-team#695. Warn unconditionally on usage to
@@ -1517,7 +1663,8 @@ pub fn rustc_short_optgroups() -> Vec<RustcOptGroup> {
             "[crate-name|file-names|sysroot|target-libdir|cfg|check-cfg|calling-conventions|\
              target-list|target-cpus|target-features|relocation-models|code-models|\
              tls-models|target-spec-json|all-target-specs-json|native-static-libs|\
-             stack-protector-strategies|link-args|deployment-target]",
+             stack-protector-strategies|link-args|deployment-target|deployment-target-min|\
+             apple-platform|apple-llvm-target|apple-sdk-platform-path-hint]",
         ),
         opt::flagmulti_s("g", "", "Equivalent to -C debuginfo=2"),
         opt::flagmulti_s("O", "", "Equivalent to -C opt-level=2"),
@@ -1934,12 +2081,16 @@ fn collect_print_requests(
     const PRINT_KINDS: &[(&str, PrintKind)] = &[
         // tidy-alphabetical-start
         ("all-target-specs-json", PrintKind::AllTargetSpecs),
+        ("apple-llvm-target", PrintKind::AppleLlvmTarget),
+        ("apple-platform", PrintKind::ApplePlatform),
+        ("apple-sdk-platform-path-hint", PrintKind::AppleSdkPlatformPathHint),
         ("calling-conventions", PrintKind::CallingConventions),
         ("cfg", PrintKind::Cfg),
         ("check-cfg", PrintKind::CheckCfg),
         ("code-models", PrintKind::CodeModels),
         ("crate-name", PrintKind::CrateName),
         ("deployment-target", PrintKind::DeploymentTarget),
+        ("deployment-target-min", PrintKind::DeploymentTargetMin),
         ("file-names", PrintKind::FileNames),
         ("link-args", PrintKind::LinkArgs),
         ("native-static-libs", PrintKind::NativeStaticLibs),
@@ -1996,6 +2147,46 @@ fn collect_print_requests(
                     );
                 }
             }
+            Some((_, PrintKind::DeploymentTargetMin)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::DeploymentTargetMin
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the deployment-target-min print option",
+                    );
+                }
+            }
+            Some((_, PrintKind::ApplePlatform)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::ApplePlatform
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the apple-platform print option",
+                    );
+                }
+            }
+            Some((_, PrintKind::AppleLlvmTarget)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::AppleLlvmTarget
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the apple-llvm-target print option",
+                    );
+                }
+            }
+            Some((_, PrintKind::AppleSdkPlatformPathHint)) => {
+                if unstable_opts.unstable_options {
+                    PrintKind::AppleSdkPlatformPathHint
+                } else {
+                    early_dcx.early_fatal(
+                        "the `-Z unstable-options` flag must also be passed to \
+                         enable the apple-sdk-platform-path-hint print option",
+                    );
+                }
+            }
             Some(&(_, print_kind)) => print_kind,
             None => {
                 let prints =
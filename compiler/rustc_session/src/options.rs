@@ -1641,6 +1641,42 @@ pub(crate) fn parse_mir_include_spans(slot: &mut MirIncludeSpans, v: Option<&str
         "only allow the listed language features to be enabled in code (comma separated)"),
     always_encode_mir: bool = (false, parse_bool, [TRACKED],
         "encode MIR of all functions into the crate metadata (default: no)"),
+    apple_application_extension: bool = (false, parse_bool, [TRACKED],
+        "link Apple targets as app extensions, which restricts the linked APIs to those that \
+        are available to app extensions (default: no)"),
+    apple_dead_strip_dylibs: bool = (false, parse_bool, [TRACKED],
+        "let the linker remove unused dynamic libraries on Apple targets (default: no)"),
+    apple_bitcode_marker: bool = (false, parse_bool, [TRACKED],
+        "let the linker emit an empty bitcode bundle marker on Apple targets (default: no)"),
+    apple_cc_target: bool = (false, parse_bool, [TRACKED],
+        "pass `-target` to `cc` when linking for macOS, which requires Clang \
+        (default: no)"),
+    apple_cc_version_min: bool = (false, parse_bool, [TRACKED],
+        "pass `-arch` and `-m<os>-version-min` instead of `-target` to `cc` when \
+        linking for Apple targets (default: no)"),
+    apple_deployment_target: Option<String> = (None, parse_opt_string, [TRACKED],
+        "the deployment target of Apple targets, taking precedence over the \
+        `*_DEPLOYMENT_TARGET` environment variables"),
+    apple_keep_ar_date: bool = (false, parse_bool, [TRACKED],
+        "don't set `ZERO_AR_DATE` for the linker on Apple targets, so that it records the \
+        modification times of object files (default: no)"),
+    apple_legacy_version_min: bool = (false, parse_bool, [TRACKED],
+        "make the linker emit the legacy `LC_VERSION_MIN_*` load commands on Apple \
+        targets, by passing it an older deployment target (default: no)"),
+    apple_no_adhoc_codesign: bool = (false, parse_bool, [TRACKED],
+        "don't let the linker ad-hoc code sign the output on Apple targets (default: no)"),
+    apple_reproducible: Option<String> = (None, parse_opt_string, [TRACKED],
+        "make the output of Apple targets reproducible, recording the given SDK version \
+        (default: no)"),
+    apple_strict_sdk: bool = (false, parse_bool, [TRACKED],
+        "require the version of the SDK in `SDKROOT` for Apple targets, instead of recording the \
+        deployment target as the SDK version (default: no)"),
+    apple_zero_sdk_version: bool = (false, parse_bool, [TRACKED],
+        "pass `0.0.0` as the SDK version to the linker on Apple targets, instead of the \
+        deployment target (default: no)"),
+    apple_zippered: bool = (false, parse_bool, [TRACKED],
+        "link zippered macOS binaries, which can also be loaded by Mac Catalyst processes \
+        (default: no)"),
     assert_incr_state: Option<String> = (None, parse_opt_string, [UNTRACKED],
         "assert that the incremental cache is in given state: \
          either `loaded` or `not-loaded`."),
@@ -30,8 +30,9 @@
 use rustc_span::{FileNameDisplayPreference, RealFileName, Span, Symbol};
 use rustc_target::asm::InlineAsmArch;
 use rustc_target::spec::{
-    CodeModel, DebuginfoKind, PanicStrategy, RelocModel, RelroLevel, SanitizerSet,
-    SmallDataThresholdSupport, SplitDebuginfo, StackProtector, Target, TargetTriple, TlsModel,
+    apple_deployment_target_file_path, CodeModel, DebuginfoKind, PanicStrategy, RelocModel,
+    RelroLevel, SanitizerSet, SmallDataThresholdSupport, SplitDebuginfo, StackProtector, Target,
+    TargetTriple, TlsModel, APPLE_DEPLOYMENT_TARGET_FILE, APPLE_KEEP_SDKROOT,
 };
 
 use crate::code_stats::CodeStats;
@@ -1069,6 +1070,22 @@ pub fn build_session(
     let mut psess = ParseSess::with_dcx(dcx, source_map);
     psess.assume_incomplete_release = sopts.unstable_opts.assume_incomplete_release;
 
+    // The deployment target of Apple targets can be read from a file of build
+    // settings, so rebuild when it (or the variable pointing to it) changes.
+    if target.is_like_osx {
+        let path =
+            apple_deployment_target_file_path().map(|path| Symbol::intern(&path.to_string_lossy()));
+        psess.env_depinfo.get_mut().insert((Symbol::intern(APPLE_DEPLOYMENT_TARGET_FILE), path));
+        if let Some(path) = path {
+            psess.file_depinfo.get_mut().insert(path);
+        }
+        // Whether `SDKROOT` is passed through to the linker depends on this.
+        if target.os == "macos" {
+            let keep_sdkroot = std::env::var(APPLE_KEEP_SDKROOT).ok().map(|v| Symbol::intern(&v));
+            psess.env_depinfo.get_mut().insert((Symbol::intern(APPLE_KEEP_SDKROOT), keep_sdkroot));
+        }
+    }
+
     let host_triple = config::host_triple();
     let target_triple = sopts.target_triple.triple();
     let host_tlib_path = Lrc::new(SearchPath::from_sysroot_and_triple(&sysroot, host_triple));
@@ -1,19 +1,195 @@
 use std::borrow::Cow;
-use std::env;
-use std::num::ParseIntError;
+use std::num::{IntErrorKind, ParseIntError};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::{env, fmt, fs, mem};
 
 use crate::spec::{
     add_link_args, add_link_args_iter, cvs, Cc, DebuginfoKind, FramePointer, LinkArgs,
-    LinkerFlavor, Lld, SplitDebuginfo, StackProbeType, StaticCow, Target, TargetOptions,
+    LinkerFlavor, Lld, SanitizerSet, SplitDebuginfo, StackProbeType, StaticCow, Target,
+    TargetOptions,
 };
 
 #[cfg(test)]
 mod tests;
 
+/// An OS version triple, as used in deployment targets and SDK versions.
+///
+/// The size of the numbers here are limited by Mach-O's `LC_BUILD_VERSION`.
+pub type OSVersion = (u16, u8, u8);
+
+/// An error describing why an OS, architecture and ABI do not form a valid
+/// Apple target.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AppleTargetError {
+    /// The OS is not one of Apple's operating systems.
+    UnknownOs(String),
+    /// The ABI is not one of `""`, `"sim"` or `"macabi"`.
+    UnknownAbi(String),
+    /// The architecture is not supported on the OS.
+    UnsupportedArch { os: String, arch: &'static str },
+    /// The architecture is only supported in the simulator for the OS.
+    SimulatorOnlyArch { os: String, arch: &'static str },
+    /// The architecture is not used by the simulator for the OS, only by
+    /// devices.
+    DeviceOnlyArch { os: String, arch: &'static str },
+    /// The ABI is not supported on the OS.
+    UnsupportedAbi { os: String, abi: &'static str },
+    /// The deployment target given on the command line is not a valid version.
+    InvalidDeploymentTarget(String),
+    /// The SDK version given on the command line is not a valid version.
+    InvalidSdkVersion(String),
+    /// No deployment target was given, but one is required, e.g. for
+    /// reproducible builds.
+    ImplicitDeploymentTarget { env_var: &'static str },
+    /// The SDK version is required, but `SDKROOT` is not set, or its version
+    /// cannot be determined.
+    UnknownSdkVersion { sdk_root: Option<String> },
+    /// The target's `apple-max-deployment-target` is not a valid version.
+    InvalidMaxDeploymentTarget(String),
+    /// The deployment target is higher than the target's
+    /// `apple-max-deployment-target`.
+    DeploymentTargetTooHigh { version: OSVersion, max: OSVersion },
+    /// The deployment target is lower than what the architecture requires.
+    DeploymentTargetTooLow { arch: &'static str, version: OSVersion, min: OSVersion },
+    /// The `native` deployment target was requested, but the target is not
+    /// macOS, or the host is not macOS.
+    NativeDeploymentTargetUnsupported,
+    /// The `native` deployment target was requested, but the version of the
+    /// running macOS cannot be determined.
+    UnknownHostVersion,
+    /// The file in `RUSTC_DEPLOYMENT_TARGET_FILE` cannot be read.
+    UnreadableDeploymentTargetFile { path: String, error: String },
+    /// A zippered binary was requested for another OS than macOS.
+    ZipperedNonMacOs { os: String },
+    /// The OS and ABI can only be selected with `-target` when linking
+    /// through CC.
+    NoCcVersionMin { os: String, abi: &'static str },
+    /// The arguments that tell CC which target to link for cannot be found.
+    UnknownCcTargetArgs(String),
+    /// The legacy `LC_VERSION_MIN_*` load commands were requested for a
+    /// platform that has never had them.
+    NoLegacyVersionMin { os: String, abi: &'static str },
+}
+
+impl fmt::Display for AppleTargetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownOs(os) => write!(f, "`{os}` is not an Apple OS"),
+            Self::UnknownAbi(abi) => write!(f, "invalid abi `{abi}` for Apple target"),
+            Self::UnsupportedArch { os, arch } => {
+                write!(f, "the `{arch}` architecture is not supported on {os}")
+            }
+            Self::SimulatorOnlyArch { os, arch } => {
+                write!(f, "the `{arch}` architecture is only supported in the {os} simulator")
+            }
+            Self::DeviceOnlyArch { os, arch } => {
+                write!(f, "the `{arch}` architecture is not supported in the {os} simulator")
+            }
+            Self::UnsupportedAbi { os, abi: "macabi" } => {
+                write!(f, "Mac Catalyst is only valid for iOS targets, not {os}")
+            }
+            Self::UnsupportedAbi { os, abi: "sim" } => write!(f, "there is no simulator for {os}"),
+            Self::UnsupportedAbi { os, abi } => {
+                write!(f, "the `{abi}` abi is not supported on {os}")
+            }
+            Self::InvalidDeploymentTarget(version) => {
+                write!(f, "invalid deployment target `{version}`")
+            }
+            Self::InvalidSdkVersion(version) => write!(f, "invalid SDK version `{version}`"),
+            Self::ImplicitDeploymentTarget { env_var } => write!(
+                f,
+                "reproducible builds require an explicit deployment target, set \
+                 `{env_var}` or pass `-Zapple-deployment-target`"
+            ),
+            Self::UnknownSdkVersion { sdk_root: None } => write!(
+                f,
+                "the SDK version is not known, set `SDKROOT` to the SDK or pass its version \
+                 with `-Zapple-reproducible`"
+            ),
+            Self::UnknownSdkVersion { sdk_root: Some(sdk_root) } => {
+                write!(f, "cannot determine the version of the SDK at `{sdk_root}`")
+            }
+            Self::InvalidMaxDeploymentTarget(max) => {
+                write!(f, "invalid `apple-max-deployment-target` `{max}`")
+            }
+            Self::DeploymentTargetTooHigh { version: (major, minor, patch), max } => {
+                let (max_major, max_minor, max_patch) = max;
+                write!(
+                    f,
+                    "deployment target {major}.{minor}.{patch} is higher than the maximum \
+                     allowed deployment target {max_major}.{max_minor}.{max_patch}"
+                )
+            }
+            Self::DeploymentTargetTooLow { arch, version: (major, minor, patch), min } => {
+                let (min_major, min_minor, min_patch) = min;
+                write!(
+                    f,
+                    "deployment target {major}.{minor}.{patch} is lower than the minimum \
+                     deployment target {min_major}.{min_minor}.{min_patch} of `{arch}`"
+                )
+            }
+            Self::NativeDeploymentTargetUnsupported => write!(
+                f,
+                "the `native` deployment target is only supported when compiling for macOS \
+                 on macOS"
+            ),
+            Self::UnknownHostVersion => {
+                write!(f, "cannot determine the version of the running macOS")
+            }
+            Self::UnreadableDeploymentTargetFile { path, error } => write!(
+                f,
+                "cannot read the build settings at `{path}` from \
+                 `{DEPLOYMENT_TARGET_FILE}`: {error}"
+            ),
+            Self::ZipperedNonMacOs { os } => {
+                write!(f, "only macOS binaries can be zippered, not {os} binaries")
+            }
+            Self::NoCcVersionMin { os, abi } => {
+                let platform = match *abi {
+                    "macabi" => "Mac Catalyst".to_string(),
+                    "sim" => format!("the {os} simulator"),
+                    _ => os.clone(),
+                };
+                write!(
+                    f,
+                    "{platform} can only be selected with `-target` when linking through `cc`"
+                )
+            }
+            Self::NoLegacyVersionMin { os, abi } => {
+                let platform = if *abi == "macabi" { "Mac Catalyst" } else { os };
+                write!(f, "{platform} has no legacy `LC_VERSION_MIN_*` load command")
+            }
+            Self::UnknownCcTargetArgs(llvm_target) => write!(
+                f,
+                "cannot find the arguments that select the target for `cc` in the linker \
+                 arguments of `{llvm_target}`"
+            ),
+        }
+    }
+}
+
+/// Define `Arch` along with `Arch::all`, so that the two can't disagree.
+macro_rules! arches {
+    ($($arch:ident),* $(,)?) => {
+        #[allow(non_camel_case_types)]
+        #[derive(Copy, Clone, PartialEq)]
+        pub enum Arch {
+            $($arch,)*
+        }
+
+        impl Arch {
+            /// Every architecture.
+            pub(crate) fn all() -> &'static [Arch] {
+                &[$(Arch::$arch),*]
+            }
+        }
+    };
+}
+
 use Arch::*;
-#[allow(non_camel_case_types)]
-#[derive(Copy, Clone, PartialEq)]
-pub(crate) enum Arch {
+arches! {
+    Armv6,
     Armv7k,
     Armv7s,
     Arm64,
@@ -28,6 +204,7 @@ pub(crate) enum Arch {
 impl Arch {
     fn target_name(self) -> &'static str {
         match self {
+            Armv6 => "armv6",
             Armv7k => "armv7k",
             Armv7s => "armv7s",
             Arm64 => "arm64",
@@ -45,9 +222,13 @@ fn ld_arch(self) -> &'static str {
         // Supported architecture names can be found in the source:
         // https://github.com/apple-oss-distributions/ld64/blob/ld64-951.9/src/abstraction/MachOFileAbstraction.hpp#L578-L648
         match self {
+            Armv6 => "armv6",
             Armv7k => "armv7k",
             Armv7s => "armv7s",
             Arm64 => "arm64",
+            // This is what makes ld64 link an arm64e (pointer authentication)
+            // binary; it needs no further flags, as the objects themselves are
+            // already marked with the `arm64e` CPU subtype by LLVM.
             Arm64e => "arm64e",
             Arm64_32 => "arm64_32",
             // ld64 doesn't understand i686, so fall back to i386 instead
@@ -59,29 +240,72 @@ fn ld_arch(self) -> &'static str {
         }
     }
 
+    /// Whether the linker is told to link a different, older architecture
+    /// than the one requested, see `ld_arch`.
+    fn ld_arch_is_downgraded(self) -> bool {
+        self.ld_arch() != self.target_name()
+    }
+
+    /// The width of pointers in bits, i.e. `target_pointer_width`.
+    ///
+    /// Note that this is 32 for `arm64_32`, which is a 64-bit architecture,
+    /// but uses 32-bit pointers (ILP32).
+    pub fn pointer_width(self) -> u32 {
+        match self {
+            Armv6 | Armv7k | Armv7s | Arm64_32 | I386 | I686 => 32,
+            Arm64 | Arm64e | X86_64 | X86_64h => 64,
+        }
+    }
+
+    /// Whether pointers are 32 bits wide, see `pointer_width`.
+    pub fn is_32_bit(self) -> bool {
+        self.pointer_width() == 32
+    }
+
     pub(crate) fn target_arch(self) -> Cow<'static, str> {
         Cow::Borrowed(match self {
-            Armv7k | Armv7s => "arm",
+            Armv6 | Armv7k | Armv7s => "arm",
             Arm64 | Arm64e | Arm64_32 => "aarch64",
             I386 | I686 => "x86",
             X86_64 | X86_64h => "x86_64",
         })
     }
 
-    fn target_cpu(self, abi: TargetAbi) -> &'static str {
+    /// The default CPU for this architecture on the given OS and ABI.
+    pub(crate) fn target_cpu(self, os: &str, abi: TargetAbi) -> &'static str {
         match self {
+            // Every ARM64 Mac has at least an M1.
+            Arm64 | Arm64e if os == "macos" => "apple-m1",
+            // The CPU of the original iPhone and the first iPod touches.
+            Armv6 => "arm1176jzf-s",
             Armv7k => "cortex-a8",
             Armv7s => "swift", // iOS 10 is only supported on iPhone 5 or higher.
+            // Only Apple Watches with at least an S9, whose CPU cores are
+            // those of the A16, run plain ARM64 code.
+            Arm64 if os == "watchos" && abi == TargetAbi::Normal => "apple-a16",
+            // The ARM64 tvOS and watchOS simulators only run on Apple silicon
+            // Macs, so they can use the macOS baseline. The iOS simulator
+            // keeps `apple-a12` below.
+            Arm64 if matches!(os, "tvos" | "watchos") && abi == TargetAbi::Simulator => "apple-m1",
             Arm64 => match abi {
                 TargetAbi::Normal => "apple-a7",
                 TargetAbi::Simulator => "apple-a12",
                 TargetAbi::MacCatalyst => "apple-a12",
             },
+            // The arm64e ABI needs at least an A12. This doesn't depend on the
+            // deployment target, as iPadOS (which is also `ios`) still supports
+            // A12 iPads in every version so far, up to and including 26.
             Arm64e => "apple-a12",
             Arm64_32 => "apple-s4",
+            // The 32-bit iOS simulator isn't bound by the macOS floor below,
+            // and Clang uses the baseline of the oldest x86 Macs for it.
+            I386 | I686 if os != "macos" => "yonah",
             // Only macOS 10.12+ is supported, which means
             // all x86_64/x86 CPUs must be running at least penryn
             // https://github.com/llvm/llvm-project/blob/01f924d0e37a5deae51df0d77e10a15b63aa0c0f/clang/lib/Driver/ToolChains/Arch/X86.cpp#L79-L82
+            //
+            // The x86_64 simulators use the same baseline, as they are only
+            // useful on Macs that can run a supported macOS anyhow.
             I386 | I686 => "penryn",
             X86_64 => "penryn",
             // Note: `core-avx2` is slightly more advanced than `x86_64h`, see
@@ -91,9 +315,47 @@ fn target_cpu(self, abi: TargetAbi) -> &'static str {
         }
     }
 
+    /// The OSes that this architecture can be used on. x86 without the
+    /// simulator or Mac Catalyst ABIs is only valid on macOS, see `validate`.
+    fn supported_oses(self) -> &'static [&'static str] {
+        match self {
+            Armv7k | Arm64_32 => &["watchos"],
+            Armv6 | Armv7s | I386 => &["ios"],
+            Arm64 => &["macos", "ios", "tvos", "watchos", "visionos"],
+            Arm64e => &["macos", "ios"],
+            I686 => &["macos"],
+            // Intel Macs with a Haswell or newer CPU can also run the iOS and
+            // tvOS simulators with the `x86_64h` baseline.
+            X86_64h => &["macos", "ios", "tvos"],
+            X86_64 => &["macos", "ios", "tvos", "watchos"],
+        }
+    }
+
+    /// The frame pointers that are kept on this architecture.
+    ///
+    /// Apple's ABIs require a valid chain of frame records, which e.g.
+    /// Instruments and the crash reporter use to walk the stack, so no Apple
+    /// target may use `FramePointer::MayOmit`. Apple's ARM64 ABI allows leaf
+    /// functions to not create a frame record, so like Clang, the AArch64
+    /// architectures (including `arm64_32`) only keep the frame pointer in
+    /// non-leaf functions.
+    /// <https://developer.apple.com/documentation/xcode/writing-arm64-code-for-apple-platforms#Respect-the-purpose-of-specific-CPU-registers>
+    fn frame_pointer(self) -> FramePointer {
+        match self {
+            Arm64 | Arm64e | Arm64_32 => FramePointer::NonLeaf,
+            Armv6 | Armv7k | Armv7s | I386 | I686 | X86_64 | X86_64h => FramePointer::Always,
+        }
+    }
+
+    /// The default stack probes for the architecture.
+    ///
+    /// Targets (or custom target specs) that prefer to call
+    /// `__rust_probestack` can override `stack_probes` in the options returned
+    /// by `base`.
     fn stack_probes(self) -> StackProbeType {
         match self {
-            Armv7k | Armv7s => StackProbeType::None,
+            // LLVM doesn't support stack probes on 32-bit ARM.
+            Armv6 | Armv7k | Armv7s => StackProbeType::None,
             Arm64 | Arm64e | Arm64_32 | I386 | I686 | X86_64 | X86_64h => StackProbeType::Inline,
         }
     }
@@ -114,20 +376,27 @@ fn target_abi(self) -> &'static str {
             Self::Simulator => "sim",
         }
     }
+
+    /// The inverse of `target_abi`, i.e. parse the `target_abi` of a target.
+    pub(crate) fn from_abi_str(abi: &str) -> Option<Self> {
+        Some(match abi {
+            "" => Self::Normal,
+            "macabi" => Self::MacCatalyst,
+            "sim" => Self::Simulator,
+            _ => return None,
+        })
+    }
 }
 
-fn pre_link_args(os: &'static str, arch: Arch, abi: TargetAbi) -> LinkArgs {
-    // From the man page for ld64 (`man ld`):
-    // > The linker accepts universal (multiple-architecture) input files,
-    // > but always creates a "thin" (single-architecture), standard Mach-O
-    // > output file. The architecture for the output file is specified using
-    // > the -arch option.
-    //
-    // The linker has heuristics to determine the desired architecture, but to
-    // be safe, and to avoid a warning, we set the architecture explicitly.
-    let mut args =
-        TargetOptions::link_args(LinkerFlavor::Darwin(Cc::No, Lld::No), &["-arch", arch.ld_arch()]);
+impl fmt::Display for TargetAbi {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.target_abi())
+    }
+}
 
+/// The values passed to ld64's `-platform_version`: The platform name, the
+/// minimum OS version, and the SDK version.
+fn platform_version(os: &str, abi: TargetAbi, version: OSVersion) -> [String; 3] {
     // From the man page for ld64 (`man ld`):
     // > This is set to indicate the platform, oldest supported version of
     // > that platform that output is to be used on, and the SDK that the
@@ -147,25 +416,102 @@ fn pre_link_args(os: &'static str, arch: Arch, abi: TargetAbi) -> LinkArgs {
     // > - visionos-simulator
     // > - xros-simulator
     // > - driverkit
+    let platform_name = match abi {
+        TargetAbi::Normal => os.to_string(),
+        TargetAbi::Simulator => format!("{os}-simulator"),
+        TargetAbi::MacCatalyst => "mac-catalyst".to_string(),
+    };
+    let (major, minor, patch) = version;
+    let min_version = format!("{major}.{minor}.{patch}");
+    // Lie about the SDK version, we don't know it here. Note that ld64
+    // rejects SDK versions lower than the deployment target, so this must
+    // never be lower than `min_version`.
+    let sdk_version = min_version.clone();
+    [platform_name, min_version, sdk_version]
+}
+
+/// Whether the linker links the given target as an older architecture than
+/// the target's own, such as `i386` for `i686-apple-darwin`.
+pub fn ld_arch_is_downgraded(target: &Target) -> bool {
+    target_arch_from_llvm_target(target).is_some_and(Arch::ld_arch_is_downgraded)
+}
+
+/// The architecture that the target was created with, as named in its LLVM
+/// target, or `None` if it's not an Apple target.
+fn target_arch_from_llvm_target(target: &Target) -> Option<Arch> {
+    platform(target)?;
+    arch_from_rust_triple(&target.llvm_target)
+}
+
+/// The architecture named by the first component of a Rust target triple.
+///
+/// E.g. `X86_64h` for `x86_64h-apple-darwin`. This also accepts LLVM target
+/// triples, which only differ in naming `aarch64` as `arm64`.
+pub fn arch_from_rust_triple(triple: &str) -> Option<Arch> {
+    let (name, _) = triple.split_once('-')?;
+    if name == "aarch64" {
+        return Some(Arm64);
+    }
+    Arch::all().iter().copied().find(|arch| arch.target_name() == name)
+}
+
+/// The deployment target and the SDK version that are passed to ld64 for the
+/// target.
+///
+/// This includes an SDK version set with `-Zapple-reproducible` or
+/// `-Zapple-strict-sdk`.
+pub fn linked_platform_version(target: &Target) -> Option<(OSVersion, OSVersion)> {
+    let args = target.pre_link_args.get(&LinkerFlavor::Darwin(Cc::No, Lld::No))?;
+    let i = args.iter().position(|arg| arg == "-platform_version")?;
+    let min_version = parse_version(args.get(i + 2)?).ok()?;
+    let sdk_version = parse_version(args.get(i + 3)?).ok()?;
+    Some((min_version, sdk_version))
+}
+
+/// The version of the SDK at `sdk_root`, as read from its `SDKSettings.json`,
+/// or failing that, from the name of the SDK directory, e.g. `MacOSX14.2.sdk`.
+pub fn sdk_root_version(sdk_root: &Path) -> Option<OSVersion> {
+    if let Ok(settings) = fs::read_to_string(sdk_root.join("SDKSettings.json"))
+        && let Some(version) = sdk_settings_version(&settings)
+    {
+        return Some(version);
+    }
+    let name = sdk_root.file_name()?.to_str()?.strip_suffix(".sdk")?;
+    parse_version(name.trim_start_matches(|c: char| c.is_ascii_alphabetic())).ok()
+}
+
+/// The `Version` in the contents of an SDK's `SDKSettings.json`.
+fn sdk_settings_version(settings: &str) -> Option<OSVersion> {
+    let settings: serde_json::Value = serde_json::from_str(settings).ok()?;
+    parse_version(settings.get("Version")?.as_str()?).ok()
+}
+
+fn pre_link_args(
+    os: &'static str,
+    arch: Arch,
+    abi: TargetAbi,
+    version: OSVersion,
+) -> Result<LinkArgs, AppleTargetError> {
+    // From the man page for ld64 (`man ld`):
+    // > The linker accepts universal (multiple-architecture) input files,
+    // > but always creates a "thin" (single-architecture), standard Mach-O
+    // > output file. The architecture for the output file is specified using
+    // > the -arch option.
     //
+    // The linker has heuristics to determine the desired architecture, but to
+    // be safe, and to avoid a warning, we set the architecture explicitly.
+    let mut args =
+        TargetOptions::link_args(LinkerFlavor::Darwin(Cc::No, Lld::No), &["-arch", arch.ld_arch()]);
+
     // Like with `-arch`, the linker can figure out the platform versions
     // itself from the binaries being linked, but to be safe, we specify the
     // desired versions here explicitly.
-    let platform_name: StaticCow<str> = match abi {
-        TargetAbi::Normal => os.into(),
-        TargetAbi::Simulator => format!("{os}-simulator").into(),
-        TargetAbi::MacCatalyst => "mac-catalyst".into(),
-    };
-    let min_version: StaticCow<str> = {
-        let (major, minor, patch) = deployment_target(os, arch, abi);
-        format!("{major}.{minor}.{patch}").into()
-    };
-    // Lie about the SDK version, we don't know it here
-    let sdk_version = min_version.clone();
+    let [platform_name, min_version, sdk_version] = platform_version(os, abi, version);
     add_link_args_iter(
         &mut args,
         LinkerFlavor::Darwin(Cc::No, Lld::No),
-        ["-platform_version".into(), platform_name, min_version, sdk_version].into_iter(),
+        ["-platform_version".into(), platform_name.into(), min_version.into(), sdk_version.into()]
+            .into_iter(),
     );
 
     // We need to communicate four things to the C compiler to be able to link:
@@ -185,34 +531,93 @@ fn pre_link_args(os: &'static str, arch: Arch, abi: TargetAbi) -> LinkArgs {
     // safely use `-target`. See also the following, where it is made explicit
     // that the recommendation by LLVM developers is to use `-target`:
     // <https://github.com/llvm/llvm-project/issues/88271>
-    if os == "macos" {
-        // `-arch` communicates the architecture.
-        //
-        // CC forwards the `-arch` to the linker, so we use the same value
-        // here intentionally.
-        add_link_args(
-            &mut args,
-            LinkerFlavor::Darwin(Cc::Yes, Lld::No),
-            &["-arch", arch.ld_arch()],
-        );
-        // The presence of `-mmacosx-version-min` makes CC default to macOS,
-        // and it sets the deployment target.
-        let (major, minor, patch) = deployment_target(os, arch, abi);
-        let opt = format!("-mmacosx-version-min={major}.{minor}.{patch}").into();
-        add_link_args_iter(&mut args, LinkerFlavor::Darwin(Cc::Yes, Lld::No), [opt].into_iter());
-        // macOS has no environment, so with these two, we've told CC all the
-        // desired parameters.
-        //
-        // We avoid `-m32`/`-m64`, as this is already encoded by `-arch`.
-    } else {
-        add_link_args_iter(
-            &mut args,
-            LinkerFlavor::Darwin(Cc::Yes, Lld::No),
-            ["-target".into(), llvm_target(os, arch, abi)].into_iter(),
-        );
+    //
+    // `-Zapple-cc-target` allows opting in to `-target` on macOS too (see
+    // `use_cc_target`), and `-Zapple-cc-version-min` opting out of it on the
+    // other OSes (see `use_cc_version_min`).
+    let cc_args = cc_target_args("apple", os, arch, abi, version, os != "macos")?;
+    add_link_args_iter(&mut args, LinkerFlavor::Darwin(Cc::Yes, Lld::No), cc_args.into_iter());
+
+    Ok(args)
+}
+
+/// The arguments that tell CC which target to link for, see `pre_link_args`.
+fn cc_target_args(
+    vendor: &str,
+    os: &str,
+    arch: Arch,
+    abi: TargetAbi,
+    version: OSVersion,
+    use_target: bool,
+) -> Result<Vec<StaticCow<str>>, AppleTargetError> {
+    if use_target {
+        return Ok(vec!["-target".into(), llvm_target(vendor, os, arch, abi, version)?]);
     }
 
-    args
+    // `-arch` communicates the architecture.
+    //
+    // CC forwards the `-arch` to the linker, so we use the same value here
+    // intentionally. The arguments of `Cc::No` are not passed when linking
+    // through CC, so the linker still only receives a single `-arch`.
+    //
+    // The presence of `-mmacosx-version-min` makes CC default to macOS, and it
+    // sets the deployment target. macOS has no environment, so with these two,
+    // we've told CC all the desired parameters. The flags of the other OSes
+    // (see `use_cc_version_min`) also select the simulator.
+    //
+    // We avoid `-m32`/`-m64`, as this is already encoded by `-arch`.
+    let flag = cc_version_min_flag(os, abi)
+        .expect("only OSes with a version min flag link without `-target`");
+    let (major, minor, patch) = version;
+    Ok(vec![
+        "-arch".into(),
+        arch.ld_arch().into(),
+        format!("-m{flag}-version-min={major}.{minor}.{patch}").into(),
+    ])
+}
+
+/// The name in CC's `-m<name>-version-min` flag for the OS and ABI, if it has
+/// one. Mac Catalyst and visionOS can only be selected with `-target`.
+fn cc_version_min_flag(os: &str, abi: TargetAbi) -> Option<&'static str> {
+    Some(match (os, abi) {
+        ("macos", TargetAbi::Normal) => "macosx",
+        ("ios", TargetAbi::Normal) => "iphoneos",
+        ("ios", TargetAbi::Simulator) => "ios-simulator",
+        ("tvos", TargetAbi::Normal) => "tvos",
+        ("tvos", TargetAbi::Simulator) => "tvos-simulator",
+        ("watchos", TargetAbi::Normal) => "watchos",
+        ("watchos", TargetAbi::Simulator) => "watchos-simulator",
+        _ => return None,
+    })
+}
+
+/// Check that the OS, architecture and ABI form a valid Apple target.
+fn validate(os: &str, arch: Arch, abi: TargetAbi) -> Result<(), AppleTargetError> {
+    os_deployment_target(os)?;
+    if !arch.supported_oses().contains(&os) {
+        return Err(AppleTargetError::UnsupportedArch { os: os.into(), arch: arch.target_name() });
+    }
+    match (os, abi) {
+        (_, TargetAbi::Normal) | ("ios", TargetAbi::MacCatalyst) => {}
+        ("macos", TargetAbi::Simulator) | (_, TargetAbi::MacCatalyst) => {
+            return Err(AppleTargetError::UnsupportedAbi { os: os.into(), abi: abi.target_abi() });
+        }
+        (_, TargetAbi::Simulator) => {}
+    }
+    // Other than on macOS, x86 is only used when running on a Mac.
+    let is_x86 = matches!(arch, I386 | I686 | X86_64 | X86_64h);
+    if is_x86 && os != "macos" && abi == TargetAbi::Normal {
+        return Err(AppleTargetError::SimulatorOnlyArch {
+            os: os.into(),
+            arch: arch.target_name(),
+        });
+    }
+    // Simulators run the architecture of the Mac, `arm64` or x86, not the
+    // 32-bit ARM architectures or the `arm64_32` of watches.
+    if matches!(arch, Armv6 | Armv7k | Armv7s | Arm64_32) && abi == TargetAbi::Simulator {
+        return Err(AppleTargetError::DeviceOnlyArch { os: os.into(), arch: arch.target_name() });
+    }
+    Ok(())
 }
 
 /// Get the base target options, LLVM target and `target_arch` from the three
@@ -222,31 +627,33 @@ pub(crate) fn base(
     os: &'static str,
     arch: Arch,
     abi: TargetAbi,
-) -> (TargetOptions, StaticCow<str>, StaticCow<str>) {
+) -> Result<(TargetOptions, StaticCow<str>, StaticCow<str>), AppleTargetError> {
+    // Resolve the deployment target once, so that everything below agrees on
+    // it even if the environment changes. It also ends up in the LLVM target,
+    // from where `deployment_target_for_target` reads it back.
+    let AppleTargetInfo { llvm_target, deployment_target: version, .. } = resolve(os, arch, abi)?;
     let opts = TargetOptions {
         abi: abi.target_abi().into(),
         os: os.into(),
-        cpu: arch.target_cpu(abi).into(),
+        cpu: arch.target_cpu(os, abi).into(),
         link_env_remove: link_env_remove(os),
         vendor: "apple".into(),
         linker_flavor: LinkerFlavor::Darwin(Cc::Yes, Lld::No),
         // macOS has -dead_strip, which doesn't rely on function_sections
         function_sections: false,
-        dynamic_linking: true,
-        pre_link_args: pre_link_args(os, arch, abi),
+        dynamic_linking: supports_dynamic_linking(os, abi, version),
+        pre_link_args: pre_link_args(os, arch, abi, version)?,
         families: cvs!["unix"],
         is_like_osx: true,
         // LLVM notes that macOS 10.11+ and iOS 9+ default
         // to v4, so we do the same.
         // https://github.com/llvm/llvm-project/blob/378778a0d10c2f8d5df8ceff81f95b6002984a4b/clang/lib/Driver/ToolChains/Darwin.cpp#L1203
         default_dwarf_version: 4,
-        frame_pointer: FramePointer::Always,
+        frame_pointer: arch.frame_pointer(),
         has_rpath: true,
         dll_suffix: ".dylib".into(),
         archive_format: "darwin".into(),
-        // Thread locals became available with iOS 8 and macOS 10.7,
-        // and both are far below our minimum.
-        has_thread_local: true,
+        has_thread_local: supports_thread_local(os, version),
         abi_return_struct_as_int: true,
         emit_debug_gdb_scripts: false,
         eh_frame_header: false,
@@ -255,6 +662,11 @@ pub(crate) fn base(
         debuginfo_kind: DebuginfoKind::DwarfDsym,
         // The historical default for macOS targets is to run `dsymutil` which
         // generates a packed version of debuginfo split from the main file.
+        //
+        // Custom targets may default to `SplitDebuginfo::Unpacked` instead,
+        // which skips `dsymutil`. The object files are then kept around, and
+        // the linker records their paths in the binary's debug map (`N_OSO`
+        // symbols), which is how debuggers find the DWARF in them.
         split_debuginfo: SplitDebuginfo::Packed,
         supported_split_debuginfo: Cow::Borrowed(&[
             SplitDebuginfo::Packed,
@@ -270,11 +682,86 @@ pub(crate) fn base(
         // this environment variable too in recent versions.
         //
         // For some more info see the commentary on #47086
-        link_env: Cow::Borrowed(&[(Cow::Borrowed("ZERO_AR_DATE"), Cow::Borrowed("1"))]),
+        //
+        // Users that want the real timestamps can opt out with
+        // `-Zapple-keep-ar-date`, see `keep_ar_date`.
+        link_env: Cow::Borrowed(&[(Cow::Borrowed(ZERO_AR_DATE.0), Cow::Borrowed(ZERO_AR_DATE.1))]),
 
         ..Default::default()
     };
-    (opts, llvm_target(os, arch, abi), arch.target_arch())
+    Ok((opts, llvm_target, arch.target_arch()))
+}
+
+/// The values that are derived from the OS, architecture and ABI of an Apple
+/// target, and from the environment.
+#[derive(Clone, Debug, PartialEq)]
+struct AppleTargetInfo {
+    llvm_target: StaticCow<str>,
+    deployment_target: OSVersion,
+    /// The SDK version that is recorded in object files, see
+    /// `object_sdk_version`.
+    sdk_version: OSVersion,
+    /// The Mach-O platform, see `platform`.
+    platform: u32,
+}
+
+/// Validate the target, and compute the values that `base` derives for it,
+/// without building the full target options.
+fn resolve(
+    os: &'static str,
+    arch: Arch,
+    abi: TargetAbi,
+) -> Result<AppleTargetInfo, AppleTargetError> {
+    validate(os, arch, abi)?;
+    let deployment_target = deployment_target(os, arch, abi)?;
+    let platform = macho_platform(os, abi).ok_or_else(|| AppleTargetError::UnknownOs(os.into()))?;
+    Ok(AppleTargetInfo {
+        llvm_target: llvm_target("apple", os, arch, abi, deployment_target)?,
+        deployment_target,
+        sdk_version: object_sdk_version(platform, deployment_target)
+            .expect("all platforms have an SDK version"),
+        platform,
+    })
+}
+
+/// A builder for the target options of an Apple target, for when a target
+/// needs to override some of the options from `base`.
+pub(crate) struct AppleTargetBuilder {
+    opts: TargetOptions,
+    llvm_target: StaticCow<str>,
+    arch: StaticCow<str>,
+}
+
+impl AppleTargetBuilder {
+    /// Start from the options returned by `base`.
+    pub(crate) fn new(
+        os: &'static str,
+        arch: Arch,
+        abi: TargetAbi,
+    ) -> Result<Self, AppleTargetError> {
+        let (opts, llvm_target, arch) = base(os, arch, abi)?;
+        Ok(Self { opts, llvm_target, arch })
+    }
+
+    pub(crate) fn max_atomic_width(mut self, max_atomic_width: u64) -> Self {
+        self.opts.max_atomic_width = Some(max_atomic_width);
+        self
+    }
+
+    pub(crate) fn features(mut self, features: impl Into<StaticCow<str>>) -> Self {
+        self.opts.features = features.into();
+        self
+    }
+
+    pub(crate) fn supported_sanitizers(mut self, supported_sanitizers: SanitizerSet) -> Self {
+        self.opts.supported_sanitizers = supported_sanitizers;
+        self
+    }
+
+    /// The final target options, LLVM target and `target_arch`, like `base`.
+    pub(crate) fn build(self) -> (TargetOptions, StaticCow<str>, StaticCow<str>) {
+        (self.opts, self.llvm_target, self.arch)
+    }
 }
 
 pub fn sdk_version(platform: u32) -> Option<(u16, u8)> {
@@ -294,177 +781,1076 @@ pub fn sdk_version(platform: u32) -> Option<(u16, u8)> {
     }
 }
 
+/// The SDK version that is recorded in the object files that `rustc` writes
+/// itself, such as the metadata object.
+///
+/// This is the version from `sdk_version`, unless `SOURCE_DATE_EPOCH` is set to
+/// ask for a reproducible build. As those versions change between `rustc`
+/// versions, it's then the deployment target, like what `pre_link_args` passes
+/// to the linker. Either way, the SDK is never older than the deployment
+/// target.
+pub fn object_sdk_version(platform: u32, deployment_target: OSVersion) -> Option<OSVersion> {
+    let (major, minor) = sdk_version(platform)?;
+    if env::var_os("SOURCE_DATE_EPOCH").is_some() {
+        return Some(deployment_target);
+    }
+    Some((major, minor, 0).max(deployment_target))
+}
+
 pub fn platform(target: &Target) -> Option<u32> {
-    Some(match (&*target.os, &*target.abi) {
+    // Unknown ABIs are treated like the normal one.
+    let abi = TargetAbi::from_abi_str(&target.abi).unwrap_or(TargetAbi::Normal);
+    macho_platform(&target.os, abi)
+}
+
+fn macho_platform(os: &str, abi: TargetAbi) -> Option<u32> {
+    Some(match (os, abi) {
         ("macos", _) => object::macho::PLATFORM_MACOS,
-        ("ios", "macabi") => object::macho::PLATFORM_MACCATALYST,
-        ("ios", "sim") => object::macho::PLATFORM_IOSSIMULATOR,
-        ("ios", _) => object::macho::PLATFORM_IOS,
-        ("watchos", "sim") => object::macho::PLATFORM_WATCHOSSIMULATOR,
+        ("ios", TargetAbi::MacCatalyst) => object::macho::PLATFORM_MACCATALYST,
+        ("ios", TargetAbi::Simulator) => object::macho::PLATFORM_IOSSIMULATOR,
+        ("ios", TargetAbi::Normal) => object::macho::PLATFORM_IOS,
+        ("watchos", TargetAbi::Simulator) => object::macho::PLATFORM_WATCHOSSIMULATOR,
         ("watchos", _) => object::macho::PLATFORM_WATCHOS,
-        ("tvos", "sim") => object::macho::PLATFORM_TVOSSIMULATOR,
+        ("tvos", TargetAbi::Simulator) => object::macho::PLATFORM_TVOSSIMULATOR,
         ("tvos", _) => object::macho::PLATFORM_TVOS,
         // FIXME: Upgrade to `object-rs` 0.33+ implementation with visionOS platform definition
-        ("visionos", "sim") => 12,
+        ("visionos", TargetAbi::Simulator) => 12,
         ("visionos", _) => 11,
         _ => return None,
     })
 }
 
+/// The name of the `PLATFORM_*` constant of a Mach-O platform.
+pub fn platform_constant_name(platform: u32) -> Option<&'static str> {
+    Some(match platform {
+        object::macho::PLATFORM_MACOS => "PLATFORM_MACOS",
+        object::macho::PLATFORM_IOS => "PLATFORM_IOS",
+        object::macho::PLATFORM_TVOS => "PLATFORM_TVOS",
+        object::macho::PLATFORM_WATCHOS => "PLATFORM_WATCHOS",
+        object::macho::PLATFORM_BRIDGEOS => "PLATFORM_BRIDGEOS",
+        object::macho::PLATFORM_MACCATALYST => "PLATFORM_MACCATALYST",
+        object::macho::PLATFORM_IOSSIMULATOR => "PLATFORM_IOSSIMULATOR",
+        object::macho::PLATFORM_TVOSSIMULATOR => "PLATFORM_TVOSSIMULATOR",
+        object::macho::PLATFORM_WATCHOSSIMULATOR => "PLATFORM_WATCHOSSIMULATOR",
+        object::macho::PLATFORM_DRIVERKIT => "PLATFORM_DRIVERKIT",
+        object::macho::PLATFORM_XROS => "PLATFORM_XROS",
+        object::macho::PLATFORM_XROSSIMULATOR => "PLATFORM_XROSSIMULATOR",
+        _ => return None,
+    })
+}
+
+/// The name of the platform whose SDK is used to link for the target, as in
+/// `Xcode.app/Contents/Developer/Platforms/<name>.platform`.
+pub fn sdk_platform_name(target: &Target) -> Option<&'static str> {
+    os_sdk_platform_name(&target.os, &target.abi)
+}
+
+/// `sdk_platform_name` for the given OS and `target_abi`.
+fn os_sdk_platform_name(os: &str, abi: &str) -> Option<&'static str> {
+    Some(match (os, abi) {
+        // Mac Catalyst uses the macOS SDK.
+        ("macos", _) | ("ios", "macabi") => "MacOSX",
+        ("ios", "sim") => "iPhoneSimulator",
+        ("ios", _) => "iPhoneOS",
+        ("watchos", "sim") => "WatchSimulator",
+        ("watchos", _) => "WatchOS",
+        ("tvos", "sim") => "AppleTVSimulator",
+        ("tvos", _) => "AppleTVOS",
+        ("visionos", "sim") => "XRSimulator",
+        ("visionos", _) => "XROS",
+        _ => return None,
+    })
+}
+
+/// Whether binaries for the given OS and deployment target describe their
+/// platform with `LC_BUILD_VERSION` instead of the older `LC_VERSION_MIN_*`
+/// load commands.
+///
+/// This matches `getMachoBuildVersionSupportedOS` in LLVM:
+/// <https://github.com/llvm/llvm-project/blob/llvmorg-18.1.8/llvm/lib/MC/MCStreamer.cpp>
+fn uses_build_version(os: &str, version: OSVersion) -> bool {
+    let (major, minor) = match os {
+        "macos" => (10, 14),
+        "ios" | "tvos" => (12, 0),
+        "watchos" => (5, 0),
+        // visionOS has never had an `LC_VERSION_MIN_*` load command.
+        "visionos" => return true,
+        _ => return false,
+    };
+    version_at_least(version, major, minor)
+}
+
+/// The minimum deployment target at which dynamic libraries are supported on
+/// the given OS.
+///
+/// `None` means that dynamic linking is supported on every version that
+/// `rustc` supports.
+fn min_dylib_deployment_target(os: &str) -> Option<OSVersion> {
+    match os {
+        // watchOS devices did not allow apps to embed their own dynamic
+        // libraries (frameworks) until watchOS 6, where apps were made
+        // independent of their iOS companion app.
+        "watchos" => Some((6, 0, 0)),
+        _ => None,
+    }
+}
+
+/// Whether dynamic libraries can be produced for the given OS, ABI and
+/// deployment target.
+fn supports_dynamic_linking(os: &str, abi: TargetAbi, version: OSVersion) -> bool {
+    match abi {
+        // The simulator runs on the host macOS, and doesn't carry the device
+        // restrictions.
+        TargetAbi::Simulator => true,
+        TargetAbi::Normal | TargetAbi::MacCatalyst => min_dylib_deployment_target(os)
+            .map_or(true, |(major, minor, _)| version_at_least(version, major, minor)),
+    }
+}
+
+/// Whether native thread locals (TLV) are available with the given OS and
+/// deployment target. They became available with macOS 10.7 and iOS 8, and
+/// with the first versions of the other OSes that `rustc` supports, so only the
+/// unusually low minimum of armv6 iOS lacks them.
+fn supports_thread_local(os: &str, version: OSVersion) -> bool {
+    match os {
+        "macos" => version_at_least(version, 10, 7),
+        "ios" => version_at_least(version, 8, 0),
+        _ => true,
+    }
+}
+
+/// The architecture and ABI of a target, as far as `deployment_target` is
+/// concerned.
+fn target_arch_and_abi(target: &Target) -> Result<(Arch, TargetAbi), AppleTargetError> {
+    let arch = match arch_from_rust_triple(&target.llvm_target) {
+        Some(arch) => arch,
+        // Custom targets may use other LLVM targets, then only distinguish
+        // what `deployment_target` needs to.
+        None if target.arch == "aarch64" => Arch::Arm64,
+        None => Arch::X86_64,
+    };
+    let abi = TargetAbi::from_abi_str(&target.abi)
+        .ok_or_else(|| AppleTargetError::UnknownAbi(target.abi.to_string()))?;
+    Ok((arch, abi))
+}
+
 /// Hack for calling `deployment_target` outside of this module.
-pub fn deployment_target_for_target(target: &Target) -> (u16, u8, u8) {
-    let arch = if target.llvm_target.starts_with("arm64e") {
-        Arch::Arm64e
-    } else if target.arch == "aarch64" {
-        Arch::Arm64
-    } else {
-        // Dummy architecture, only used by `deployment_target` anyhow
-        Arch::X86_64
+///
+/// The deployment target is read back from the LLVM target if it contains
+/// one (as is the case for all built-in targets), and only computed from the
+/// environment otherwise.
+pub fn deployment_target_for_target(target: &Target) -> Result<OSVersion, AppleTargetError> {
+    let (arch, abi) = target_arch_and_abi(target)?;
+    if platform(target).is_none() {
+        return Err(AppleTargetError::UnknownOs(target.os.to_string()));
+    }
+    let version = match deployment_target_from_llvm_target(&target.llvm_target) {
+        // `deployment_target` raises too low versions, targets with a version
+        // in their LLVM target must do so themselves. The arm64e ABI is unusable
+        // on older versions, so there it's an error.
+        Some(version) if arch == Arch::Arm64e => {
+            let min = min_deployment_target(&target.os, arch, abi)?;
+            if version < min {
+                return Err(AppleTargetError::DeploymentTargetTooLow {
+                    arch: arch.target_name(),
+                    version,
+                    min,
+                });
+            }
+            version
+        }
+        Some(version) => version,
+        None => deployment_target(&target.os, arch, abi)?,
     };
-    let abi = match &*target.abi {
-        "macabi" => TargetAbi::MacCatalyst,
-        "sim" => TargetAbi::Simulator,
-        "" => TargetAbi::Normal,
-        abi => unreachable!("invalid abi '{abi}' for Apple target"),
+    if let Some(max) = &target.apple_max_deployment_target {
+        let max = parse_version(max)
+            .map_err(|_| AppleTargetError::InvalidMaxDeploymentTarget(max.to_string()))?;
+        if version > max {
+            return Err(AppleTargetError::DeploymentTargetTooHigh { version, max });
+        }
+    }
+    Ok(version)
+}
+
+/// Change the deployment target of an Apple target, e.g. to the one given
+/// with `-Zapple-deployment-target`. `native` stands for the
+/// `native_deployment_target`.
+///
+/// This takes precedence over the environment. Like with the environment,
+/// versions that are too low are raised to the minimum. This updates the LLVM
+/// target, and the arguments that `pre_link_args` used to communicate the
+/// deployment target to the linker.
+pub fn override_deployment_target(
+    target: &mut Target,
+    version: &str,
+) -> Result<(), AppleTargetError> {
+    let requested = match version {
+        "native" => native_deployment_target(target)?,
+        _ => parse_version(version)
+            .map_err(|_| AppleTargetError::InvalidDeploymentTarget(version.to_string()))?,
     };
-    deployment_target(&target.os, arch, abi)
+    let (arch, abi) = target_arch_and_abi(target)?;
+    let old = deployment_target_for_target(target)?;
+    let new = requested.max(min_deployment_target(&target.os, arch, abi)?);
+
+    let new_llvm_target = llvm_target(&target.vendor, &target.os, arch, abi, new)?;
+    let old_llvm_target = mem::replace(&mut target.llvm_target, new_llvm_target.clone());
+    replace_linked_version(target, abi, (old, &old_llvm_target), (new, new_llvm_target));
+    target.options.dynamic_linking = supports_dynamic_linking(&target.os, abi, new);
+    target.options.has_thread_local = supports_thread_local(&target.os, new);
+    // Check against `apple-max-deployment-target`.
+    deployment_target_for_target(target).map(|_| ())
 }
 
-/// Get the deployment target based on the standard environment variables, or
-/// fall back to a sane default.
-fn deployment_target(os: &str, arch: Arch, abi: TargetAbi) -> (u16, u8, u8) {
+/// Replace the deployment target that `pre_link_args` communicated to the
+/// linker, along with the LLVM target that contains it, by another version.
+///
+/// The SDK version is raised to the new version if it is lower.
+fn replace_linked_version(
+    target: &mut Target,
+    abi: TargetAbi,
+    (old, old_llvm_target): (OSVersion, &str),
+    (new, new_llvm_target): (OSVersion, StaticCow<str>),
+) {
+    let fmt_version = |(major, minor, patch): OSVersion| format!("{major}.{minor}.{patch}");
+    let (old_version, new_version) = (fmt_version(old), fmt_version(new));
+    let version_min_flag = cc_version_min_flag(&target.os, abi).unwrap_or("macosx");
+    for args in target.options.pre_link_args.values_mut() {
+        for i in 0..args.len() {
+            if args[i] == old_llvm_target {
+                args[i] = new_llvm_target.clone();
+            } else if args[i] == format!("-m{version_min_flag}-version-min={old_version}") {
+                args[i] = format!("-m{version_min_flag}-version-min={new_version}").into();
+            } else if args[i] == "-platform_version"
+                && args.get(i + 2) == Some(&old_version.as_str().into())
+            {
+                args[i + 2] = new_version.clone().into();
+                // The SDK version must not be lower than the deployment target.
+                if let Some(sdk) = args.get(i + 3).and_then(|sdk| parse_version(sdk).ok())
+                    && sdk < new
+                {
+                    args[i + 3] = new_version.clone().into();
+                }
+            }
+        }
+    }
+}
+
+/// The deployment target for running on the current macOS and later, for
+/// `-Zapple-deployment-target=native`.
+///
+/// This is the version of the running macOS, unless a deployment target is
+/// requested in the environment (or the build settings file), which is then
+/// used as usual. Like other deployment targets, it's raised to the minimum.
+fn native_deployment_target(target: &Target) -> Result<OSVersion, AppleTargetError> {
+    let (arch, abi) = target_arch_and_abi(target)?;
+    if target.os != "macos" || !cfg!(target_os = "macos") {
+        return Err(AppleTargetError::NativeDeploymentTargetUnsupported);
+    }
+    let version = match requested_deployment_target(&target.os, arch, abi)? {
+        Some(version) => version,
+        None => host_macos_version().ok_or(AppleTargetError::UnknownHostVersion)?,
+    };
+    Ok(version.max(min_deployment_target(&target.os, arch, abi)?))
+}
+
+/// The version of the running macOS, from the `SystemVersion.plist` that
+/// `sw_vers` reads as well.
+fn host_macos_version() -> Option<OSVersion> {
+    let plist = fs::read_to_string("/System/Library/CoreServices/SystemVersion.plist").ok()?;
+    plist_product_version(&plist)
+}
+
+/// The `ProductVersion` in the contents of a `SystemVersion.plist`, which is
+/// an XML property list.
+fn plist_product_version(plist: &str) -> Option<OSVersion> {
+    let (_, rest) = plist.split_once("<key>ProductVersion</key>")?;
+    let version = rest.trim_start().strip_prefix("<string>")?.split_once("</string>")?.0;
+    parse_version(version.trim()).ok()
+}
+
+/// Make the output of an Apple target independent of what is detected on the
+/// build machine, for `-Zapple-reproducible`.
+///
+/// This pins the SDK version passed to the linker to `sdk_version` (it is
+/// still raised to the deployment target, which ld64 requires), and always
+/// sets `ZERO_AR_DATE`, even after `keep_ar_date`. The deployment
+/// target must be given explicitly, either in the environment (or the build
+/// settings file), or with `-Zapple-deployment-target` as indicated by
+/// `explicit_deployment_target`, instead of falling back to the default.
+pub fn make_reproducible(
+    target: &mut Target,
+    sdk_version: &str,
+    explicit_deployment_target: bool,
+) -> Result<(), AppleTargetError> {
+    let sdk_version = parse_sdk_version(sdk_version)?;
+    let (arch, abi) = target_arch_and_abi(target)?;
+    if !explicit_deployment_target && requested_deployment_target(&target.os, arch, abi)?.is_none()
+    {
+        let (_, env_vars) = os_deployment_target(&target.os)?;
+        return Err(AppleTargetError::ImplicitDeploymentTarget { env_var: env_vars[0] });
+    }
+    pin_sdk_version(target, sdk_version);
+    if !zeroes_ar_date(target) {
+        target.options.link_env.to_mut().push((ZERO_AR_DATE.0.into(), ZERO_AR_DATE.1.into()));
+    }
+    Ok(())
+}
+
+/// Pass the version of the SDK at `sdk_root` to the linker, for
+/// `-Zapple-strict-sdk`.
+///
+/// This replaces the deployment target as the SDK version. `sdk_root` is the
+/// value of `SDKROOT`, it is ignored when it is in the platform directory of
+/// another platform, like when linking.
+///
+/// Errors if there is no such SDK, or if its version cannot be determined.
+pub fn require_sdk_version(
+    target: &mut Target,
+    sdk_root: Option<&str>,
+) -> Result<OSVersion, AppleTargetError> {
+    target_arch_and_abi(target)?;
+    let platform_dir = format!("{}.platform", sdk_platform_name(target).unwrap_or_default());
+    let sdk_root = sdk_root.filter(|sdk_root| {
+        !sdk_root.split('/').any(|dir| dir.ends_with(".platform") && dir != platform_dir)
+    });
+    let Some(sdk_version) = sdk_root.and_then(|sdk_root| sdk_root_version(Path::new(sdk_root)))
+    else {
+        return Err(AppleTargetError::UnknownSdkVersion { sdk_root: sdk_root.map(Into::into) });
+    };
+    pin_sdk_version(target, sdk_version);
+    Ok(sdk_version)
+}
+
+/// Set the SDK version in all `-platform_version` arguments of the target,
+/// raised to the deployment target like in `platform_version`.
+fn pin_sdk_version(target: &mut Target, sdk_version: OSVersion) {
+    let is_macos = target.os == "macos";
+    for args in target.options.pre_link_args.values_mut() {
+        for i in 0..args.len() {
+            if args[i] != "-platform_version" || i + 3 >= args.len() {
+                continue;
+            }
+            let min_version = parse_version(&args[i + 2]).unwrap_or((0, 0, 0));
+            // Zippered binaries also record the Mac Catalyst SDK version.
+            let sdk_version = if is_macos && args[i + 1] == "mac-catalyst" {
+                mac_catalyst_version_from_macos(sdk_version)
+            } else {
+                sdk_version
+            };
+            let (major, minor, patch) = sdk_version.max(min_version);
+            args[i + 3] = format!("{major}.{minor}.{patch}").into();
+        }
+    }
+}
+
+/// Parse the SDK version given to `-Zapple-reproducible`.
+pub fn parse_sdk_version(version: &str) -> Result<OSVersion, AppleTargetError> {
+    parse_version(version).map_err(|_| AppleTargetError::InvalidSdkVersion(version.to_string()))
+}
+
+/// Link as an app extension, for `-Zapple-application-extension`, which
+/// restricts the linked APIs to those that are available to app extensions.
+pub fn link_as_application_extension(target: &mut Target) {
+    add_pre_link_args(target, &["-application_extension"], &["-fapplication-extension"]);
+}
+
+/// Don't let the linker ad-hoc code sign the output, for
+/// `-Zapple-no-adhoc-codesign`.
+///
+/// It otherwise does so by default on ARM64 macOS, which is wasted work for
+/// outputs that are signed later by other tools anyhow.
+pub fn disable_adhoc_codesign(target: &mut Target) {
+    add_pre_link_args(target, &["-no_adhoc_codesign"], &["-Wl,-no_adhoc_codesign"]);
+}
+
+/// Let the linker remove the load commands of dynamic libraries that aren't
+/// used by the output, for `-Zapple-dead-strip-dylibs`.
+///
+/// Not the default, as that can remove libraries that are loaded for their
+/// side effects, or whose symbols are only looked up at runtime.
+pub fn dead_strip_dylibs(target: &mut Target) {
+    add_pre_link_args(target, &["-dead_strip_dylibs"], &["-Wl,-dead_strip_dylibs"]);
+}
+
+/// Let the linker emit an empty bitcode bundle marker into the output, for
+/// `-Zapple-bitcode-marker`.
+///
+/// Bitcode is deprecated, but some legacy submission pipelines still check
+/// for the presence of the marker.
+pub fn emit_bitcode_marker(target: &mut Target) {
+    add_pre_link_args(
+        target,
+        &["-bitcode_bundle", "-bitcode_process_mode", "marker"],
+        &["-Wl,-bitcode_bundle", "-Wl,-bitcode_process_mode,marker"],
+    );
+}
+
+/// Link a "zippered" binary, for `-Zapple-zippered`.
+///
+/// Such binaries can be loaded both by macOS and by Mac Catalyst processes,
+/// e.g. for frameworks shared between the two. The linker then records a
+/// second `LC_BUILD_VERSION` for Mac Catalyst, with the Mac Catalyst version
+/// that corresponds to the deployment target.
+///
+/// Through CC, this uses `-darwin-target-variant`, which requires Clang.
+///
+/// Errors on targets other than macOS, or without a Mac Catalyst slice.
+pub fn zipper(target: &mut Target) -> Result<(), AppleTargetError> {
+    let (arch, _) = target_arch_and_abi(target)?;
+    if target.os != "macos" {
+        return Err(AppleTargetError::ZipperedNonMacOs { os: target.os.to_string() });
+    }
+    validate("ios", arch, TargetAbi::MacCatalyst)?;
+    let catalyst_version = mac_catalyst_version_from_macos(deployment_target_for_target(target)?)
+        .max(min_deployment_target("ios", arch, TargetAbi::MacCatalyst)?);
+
+    let [platform_name, min_version, sdk_version] =
+        platform_version("ios", TargetAbi::MacCatalyst, catalyst_version);
+    add_link_args_iter(
+        &mut target.options.pre_link_args,
+        LinkerFlavor::Darwin(Cc::No, Lld::No),
+        ["-platform_version".into(), platform_name.into(), min_version.into(), sdk_version.into()]
+            .into_iter(),
+    );
+    let variant =
+        llvm_target(&target.vendor, "ios", arch, TargetAbi::MacCatalyst, catalyst_version)?;
+    add_link_args_iter(
+        &mut target.options.pre_link_args,
+        LinkerFlavor::Darwin(Cc::Yes, Lld::No),
+        ["-darwin-target-variant".into(), variant].into_iter(),
+    );
+    Ok(())
+}
+
+/// Pass `0.0.0` as the SDK version to the linker, for
+/// `-Zapple-zero-sdk-version`.
+///
+/// By default, the deployment target is repeated as the SDK version, which
+/// ld64 records as "n/a". Zero makes it clear that the SDK version is to be
+/// filled in later, e.g. by the final link of a reproducible build. An SDK
+/// version pinned afterwards, like by `make_reproducible`, still replaces it.
+pub fn zero_sdk_version(target: &mut Target) {
+    for args in target.options.pre_link_args.values_mut() {
+        for i in 0..args.len() {
+            if args[i] == "-platform_version" && i + 3 < args.len() {
+                args[i + 3] = "0.0.0".into();
+            }
+        }
+    }
+}
+
+/// Pass `-target` to CC on macOS too, for `-Zapple-cc-target`, instead of the
+/// GCC-compatible `-arch` and `-mmacosx-version-min`.
+///
+/// Only use this if CC is known to be Clang, e.g. to use the same target as
+/// LLVM for cross-language LTO. This has no effect on other OSes, where
+/// `-target` is used by default.
+pub fn use_cc_target(target: &mut Target) -> Result<(), AppleTargetError> {
+    set_cc_use_target(target, true)
+}
+
+/// Pass `-arch` and `-m<os>-version-min` (e.g. `-miphoneos-version-min`) to
+/// CC on iOS, tvOS and watchOS too, for `-Zapple-cc-version-min`, instead of
+/// `-target`.
+///
+/// This is for GCC-like compilers that accept those but not `-target`. LLVM
+/// recommends `-target`, so only use this for compatibility with an existing
+/// build. Errors on Mac Catalyst and visionOS, which have no such flag.
+pub fn use_cc_version_min(target: &mut Target) -> Result<(), AppleTargetError> {
+    set_cc_use_target(target, false)
+}
+
+/// Replace the arguments that tell CC which target to link for (see
+/// `cc_target_args`) by the ones with or without `-target`.
+///
+/// Errors if they cannot be found, like for custom targets that pass other
+/// arguments to CC, or if the OS can only be selected with `-target`.
+fn set_cc_use_target(target: &mut Target, use_target: bool) -> Result<(), AppleTargetError> {
+    let (arch, abi) = target_arch_and_abi(target)?;
+    if cc_version_min_flag(&target.os, abi).is_none() {
+        return if use_target {
+            Ok(())
+        } else {
+            Err(AppleTargetError::NoCcVersionMin {
+                os: target.os.to_string(),
+                abi: abi.target_abi(),
+            })
+        };
+    }
+    let version = deployment_target_for_target(target)?;
+    let cc_args =
+        |use_target| cc_target_args(&target.vendor, &target.os, arch, abi, version, use_target);
+    let (old, new) = (cc_args(!use_target)?, cc_args(use_target)?);
+
+    let mut found = false;
+    for (flavor, args) in target.options.pre_link_args.iter_mut() {
+        if !matches!(flavor, LinkerFlavor::Darwin(Cc::Yes, _)) {
+            continue;
+        }
+        if let Some(start) = args.windows(old.len()).position(|window| window == old) {
+            args.splice(start..start + old.len(), new.iter().cloned());
+            found = true;
+        } else if args.windows(new.len()).any(|window| window == new) {
+            found = true;
+        }
+    }
+    if !found {
+        return Err(AppleTargetError::UnknownCcTargetArgs(target.llvm_target.to_string()));
+    }
+    Ok(())
+}
+
+/// Make the linker emit the legacy `LC_VERSION_MIN_*` load command instead of
+/// `LC_BUILD_VERSION`, for `-Zapple-legacy-version-min`.
+///
+/// This is for tools that don't understand `LC_BUILD_VERSION`. ld64 picks the
+/// load command based on the minimum OS version, so this works by passing the
+/// last OS version that used `LC_VERSION_MIN_*` to the linker if the
+/// deployment target is newer than that (see `uses_build_version`). As a
+/// result, the binary claims to support older OS versions than it actually
+/// does, so the OS won't prevent it from being loaded on those versions. The
+/// LLVM target is not changed.
+///
+/// Errors on visionOS and Mac Catalyst, which have never had
+/// `LC_VERSION_MIN_*`.
+pub fn use_legacy_version_min(target: &mut Target) -> Result<(), AppleTargetError> {
+    let (arch, abi) = target_arch_and_abi(target)?;
+    let last_version_min = match &*target.os {
+        _ if abi == TargetAbi::MacCatalyst => None,
+        "macos" => Some((10, 13, 0)),
+        "ios" | "tvos" => Some((11, 0, 0)),
+        "watchos" => Some((4, 0, 0)),
+        _ => None,
+    };
+    let Some(last_version_min) = last_version_min else {
+        return Err(AppleTargetError::NoLegacyVersionMin {
+            os: target.os.to_string(),
+            abi: abi.target_abi(),
+        });
+    };
+    let version = deployment_target_for_target(target)?;
+    if !uses_build_version(&target.os, version) {
+        return Ok(());
+    }
+    let old_llvm_target = llvm_target(&target.vendor, &target.os, arch, abi, version)?;
+    let new_llvm_target = llvm_target(&target.vendor, &target.os, arch, abi, last_version_min)?;
+    replace_linked_version(
+        target,
+        abi,
+        (version, &old_llvm_target),
+        (last_version_min, new_llvm_target),
+    );
+    Ok(())
+}
+
+/// Add the arguments to the linker when invoked directly (`ld_args`), and
+/// when invoked through CC (`cc_args`).
+fn add_pre_link_args(target: &mut Target, ld_args: &[&'static str], cc_args: &[&'static str]) {
+    let pre_link_args = &mut target.options.pre_link_args;
+    add_link_args(pre_link_args, LinkerFlavor::Darwin(Cc::No, Lld::No), ld_args);
+    add_link_args(pre_link_args, LinkerFlavor::Darwin(Cc::Yes, Lld::No), cc_args);
+}
+
+/// The minimum deployment target that `rustc` supports for the given OS,
+/// architecture and ABI, regardless of the environment.
+fn min_deployment_target(
+    os: &str,
+    arch: Arch,
+    abi: TargetAbi,
+) -> Result<OSVersion, AppleTargetError> {
+    deployment_target_min(os, arch, abi).ok_or_else(|| AppleTargetError::UnknownOs(os.into()))
+}
+
+/// Like `min_deployment_target`, but usable in `const` contexts, and `None`
+/// for unknown OSes.
+const fn deployment_target_min(os: &str, arch: Arch, abi: TargetAbi) -> Option<OSVersion> {
     // When bumping a version in here, remember to update the platform-support
     // docs too.
-    //
-    // NOTE: If you are looking for the default deployment target, prefer
-    // `rustc --print deployment-target`, as the default here may change in
-    // future `rustc` versions.
-
-    // Minimum operating system versions currently supported by `rustc`.
-    let os_min = match os {
-        "macos" => (10, 12, 0),
-        "ios" => (10, 0, 0),
-        "tvos" => (10, 0, 0),
-        "watchos" => (5, 0, 0),
-        "visionos" => (1, 0, 0),
-        _ => unreachable!("tried to get deployment target for non-Apple platform"),
+    let Some(os_min) = os_min_deployment_target(os) else {
+        return None;
     };
 
     // On certain targets it makes sense to raise the minimum OS version.
-    let min = match (os, arch, abi) {
+    //
+    // Some of these match what LLVM does, see:
+    // <https://github.com/llvm/llvm-project/blob/llvmorg-18.1.8/llvm/lib/TargetParser/Triple.cpp#L1900-L1932>
+    //
+    // Strings can't be matched on in `const fn`, hence the guards.
+    Some(match (arch, abi) {
         // Use 11.0 on Aarch64 as that's the earliest version with M1 support.
-        ("macos", Arch::Arm64 | Arch::Arm64e, _) => (11, 0, 0),
-        ("ios", Arch::Arm64e, _) => (14, 0, 0),
+        (Arch::Arm64, _) if str_eq(os, "macos") => (11, 0, 0),
+        // The arm64e ABI (pointer authentication) was only a preview for
+        // third-party code on macOS 11, and changed in incompatible ways
+        // before macOS 12, so don't try to support anything older.
+        (Arch::Arm64e, _) if str_eq(os, "macos") => (12, 0, 0),
+        (Arch::Arm64e, _) if str_eq(os, "ios") => (14, 0, 0),
+        // armv6 devices only run up to iOS 4.2.1, so they can't be held to
+        // the usual minimum.
+        (Arch::Armv6, _) if str_eq(os, "ios") => (3, 0, 0),
+        // `arm64_32` only appeared with watchOS 5, but the older `armv7k`
+        // watches also ran watchOS 4, the oldest version that current Xcode
+        // versions can still target.
+        (Arch::Armv7k, _) if str_eq(os, "watchos") => (4, 0, 0),
+        // The ARM64 slice of Mac Catalyst is only supported since Mac
+        // Catalyst 14 (macOS 11), LLVM raises lower versions when emitting
+        // object files.
+        (Arch::Arm64, TargetAbi::MacCatalyst) if str_eq(os, "ios") => (14, 0, 0),
         // Mac Catalyst defaults to 13.1 in Clang.
-        ("ios", _, TargetAbi::MacCatalyst) => (13, 1, 0),
+        (_, TargetAbi::MacCatalyst) if str_eq(os, "ios") => (13, 1, 0),
+        // The ARM64 tvOS simulator is only supported since tvOS 14, LLVM
+        // raises lower versions when emitting object files.
+        (Arch::Arm64 | Arch::Arm64e, TargetAbi::Simulator) if str_eq(os, "tvos") => (14, 0, 0),
+        // Likewise, the ARM64 watchOS simulator is only supported since
+        // watchOS 7.
+        (Arch::Arm64 | Arch::Arm64e, TargetAbi::Simulator) if str_eq(os, "watchos") => (7, 0, 0),
         _ => os_min,
+    })
+}
+
+/// The minimum OS version of the given OS in `DEPLOYMENT_TARGETS`.
+const fn os_min_deployment_target(os: &str) -> Option<OSVersion> {
+    let mut i = 0;
+    while i < DEPLOYMENT_TARGETS.len() {
+        let (target_os, os_min, _) = DEPLOYMENT_TARGETS[i];
+        if str_eq(target_os, os) {
+            return Some(os_min);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `a == b`, but usable in `const` contexts.
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The minimum deployment target for the given target, see
+/// `min_deployment_target`.
+pub fn min_deployment_target_for_target(target: &Target) -> Result<OSVersion, AppleTargetError> {
+    let (arch, abi) = target_arch_and_abi(target)?;
+    min_deployment_target(&target.os, arch, abi)
+}
+
+/// The OSes that have a deployment target, together with the minimum OS
+/// version currently supported by `rustc`, and the environment variables used
+/// to fetch the deployment target, in order of precedence.
+///
+/// When bumping a version in here, remember to update the platform-support
+/// docs too.
+const DEPLOYMENT_TARGETS: &[(&str, OSVersion, &[&str])] = &[
+    ("macos", (10, 12, 0), &["MACOSX_DEPLOYMENT_TARGET"]),
+    ("ios", (10, 0, 0), &["IPHONEOS_DEPLOYMENT_TARGET"]),
+    ("tvos", (10, 0, 0), &["TVOS_DEPLOYMENT_TARGET"]),
+    ("watchos", (5, 0, 0), &["WATCHOS_DEPLOYMENT_TARGET"]),
+    // Xcode uses the internal xrOS name, but the public name is a natural
+    // thing to try as well.
+    ("visionos", (1, 0, 0), &["XROS_DEPLOYMENT_TARGET", "VISIONOS_DEPLOYMENT_TARGET"]),
+];
+
+/// The minimum OS version and the deployment target environment variables of
+/// the given OS, see `DEPLOYMENT_TARGETS`.
+fn os_deployment_target(
+    os: &str,
+) -> Result<(OSVersion, &'static [&'static str]), AppleTargetError> {
+    DEPLOYMENT_TARGETS
+        .iter()
+        .find_map(|&(target_os, os_min, env_vars)| (target_os == os).then_some((os_min, env_vars)))
+        .ok_or_else(|| AppleTargetError::UnknownOs(os.into()))
+}
+
+/// All the `*_DEPLOYMENT_TARGET` environment variables that `rustc` may
+/// consult, e.g. for build wrappers that want to clear them.
+pub fn deployment_target_env_vars() -> &'static [&'static str] {
+    static ENV_VARS: LazyLock<Vec<&str>> = LazyLock::new(|| {
+        DEPLOYMENT_TARGETS.iter().flat_map(|(_, _, env_vars)| env_vars.iter().copied()).collect()
+    });
+    &ENV_VARS
+}
+
+/// Get the deployment target based on the standard environment variables, or
+/// fall back to a sane default.
+fn deployment_target(os: &str, arch: Arch, abi: TargetAbi) -> Result<OSVersion, AppleTargetError> {
+    // NOTE: If you are looking for the default deployment target, prefer
+    // `rustc --print deployment-target`, as the default here may change in
+    // future `rustc` versions.
+    let min = min_deployment_target(os, arch, abi)?;
+    let version = requested_deployment_target(os, arch, abi)?;
+
+    // It is common that the deployment target is set too low, e.g. on macOS
+    // Aarch64 to also target older x86_64, the user may set a lower
+    // deployment target than supported.
+    //
+    // To avoid such issues, we silently raise the deployment target here.
+    // FIXME: We want to show a warning when `version < min`.
+    Ok(version.map_or(min, |version| version.max(min)))
+}
+
+/// The deployment target requested in the build settings file or the
+/// environment, if any, before it is raised to the minimum.
+fn requested_deployment_target(
+    os: &str,
+    arch: Arch,
+    abi: TargetAbi,
+) -> Result<Option<OSVersion>, AppleTargetError> {
+    let (_, env_vars) = os_deployment_target(os)?;
+
+    // Build settings from a file take precedence over the environment, to
+    // allow mirroring the settings of an Xcode target.
+    let settings = deployment_target_file();
+    // The SDK is named like its platform in lowercase, as in `xcrun --sdk`.
+    let sdk = os_sdk_platform_name(os, abi.target_abi()).unwrap_or_default().to_ascii_lowercase();
+    let lookup = |env_vars| {
+        let deployment_target = find_deployment_target(env_vars, |var| {
+            build_setting(settings.as_deref()?, var, &sdk, arch.target_name()).map(str::to_string)
+        })
+        .or_else(|| find_deployment_target(env_vars, |var| env::var(var).ok()))?;
+        // FIXME: Report erroneous environment variable to user.
+        parse_requested_deployment_target(&deployment_target)
     };
 
-    // The environment variable used to fetch the deployment target.
-    let env_var = match os {
-        "macos" => "MACOSX_DEPLOYMENT_TARGET",
-        "ios" => "IPHONEOS_DEPLOYMENT_TARGET",
-        "watchos" => "WATCHOS_DEPLOYMENT_TARGET",
-        "tvos" => "TVOS_DEPLOYMENT_TARGET",
-        "visionos" => "XROS_DEPLOYMENT_TARGET",
-        _ => unreachable!("tried to get deployment target env var for non-Apple platform"),
+    let mut version = lookup(env_vars);
+    if abi == TargetAbi::MacCatalyst {
+        // Like Xcode, also accept the macOS deployment target for Mac
+        // Catalyst, since that's what users tend to think in terms of.
+        let (_, macos_env_vars) = os_deployment_target("macos")?;
+        if let Some(macos_version) = lookup(macos_env_vars) {
+            let ios_version = mac_catalyst_version_from_macos(macos_version);
+            version = Some(version.map_or(ios_version, |version| version.max(ios_version)));
+        }
+    }
+    Ok(version)
+}
+
+/// Parse a deployment target from the environment or the build settings file.
+///
+/// This tolerates a single leading `=`, as in `MACOSX_DEPLOYMENT_TARGET==12.0`,
+/// a common mistake in shell scripts and CI configurations.
+fn parse_requested_deployment_target(version: &str) -> Option<OSVersion> {
+    parse_version(version.strip_prefix('=').unwrap_or(version)).ok()
+}
+
+/// The Mac Catalyst (iOS) version that corresponds to the given macOS
+/// version.
+///
+/// This follows the `macOS_iOSMac` version map in the `SDKSettings.json` of
+/// the macOS SDK. Versions before macOS 10.15 map to the first Mac Catalyst
+/// version, 13.1.
+fn mac_catalyst_version_from_macos((major, minor, _): OSVersion) -> OSVersion {
+    match major {
+        ..=10 => (13, 1, 0),
+        // macOS 11.0 shipped with Mac Catalyst 14.2.
+        11 => (14, minor.saturating_add(2), 0),
+        // macOS 12.1 shipped with Mac Catalyst 15.2, but 12.0 maps to 15.0.
+        12 if minor == 0 => (15, 0, 0),
+        12 => (15, minor.saturating_add(1), 0),
+        // macOS 13.0 shipped with Mac Catalyst 16.1.
+        13 => (16, minor.saturating_add(1), 0),
+        _ => (major.saturating_add(3), minor, 0),
+    }
+}
+
+/// The value of the first of `vars` that is set, according to `lookup`.
+fn find_deployment_target(
+    vars: &[&str],
+    lookup: impl FnMut(&str) -> Option<String>,
+) -> Option<String> {
+    vars.iter().copied().find_map(lookup)
+}
+
+/// The environment variable that points to an `.xcconfig`-style file of build
+/// settings, which take precedence over the environment.
+pub const DEPLOYMENT_TARGET_FILE: &str = "RUSTC_DEPLOYMENT_TARGET_FILE";
+
+/// The path in `RUSTC_DEPLOYMENT_TARGET_FILE`, if any. The session tracks this
+/// file, as the deployment targets of every Apple target depend on it.
+pub fn deployment_target_file_path() -> Option<PathBuf> {
+    env::var_os(DEPLOYMENT_TARGET_FILE).map(PathBuf::from)
+}
+
+/// Check that the file in `RUSTC_DEPLOYMENT_TARGET_FILE`, if any, can be read.
+///
+/// Deployment targets are resolved while loading the target, before errors
+/// can be reported, and an unreadable file is ignored there.
+pub fn check_deployment_target_file() -> Result<(), AppleTargetError> {
+    let Some(path) = deployment_target_file_path() else {
+        return Ok(());
     };
+    match fs::read_to_string(&path) {
+        Ok(_) => Ok(()),
+        Err(error) => Err(AppleTargetError::UnreadableDeploymentTargetFile {
+            path: path.display().to_string(),
+            error: error.to_string(),
+        }),
+    }
+}
 
-    if let Ok(deployment_target) = env::var(env_var) {
-        match parse_version(&deployment_target) {
-            // It is common that the deployment target is set too low, e.g. on
-            // macOS Aarch64 to also target older x86_64, the user may set a
-            // lower deployment target than supported.
-            //
-            // To avoid such issues, we silently raise the deployment target
-            // here.
-            // FIXME: We want to show a warning when `version < os_min`.
-            Ok(version) => version.max(min),
-            // FIXME: Report erroneous environment variable to user.
-            Err(_) => min,
+/// The contents of the `.xcconfig`-style file pointed to by
+/// `RUSTC_DEPLOYMENT_TARGET_FILE`, if any.
+fn deployment_target_file() -> Option<String> {
+    // The session reports an unreadable file, see `check_deployment_target_file`.
+    fs::read_to_string(deployment_target_file_path()?).ok()
+}
+
+/// Look up the value of `key` in `KEY = VALUE` build settings, such as those
+/// in an `.xcconfig` file, when building with the SDK `sdk` for the
+/// architecture `arch`.
+///
+/// Like in Xcode, settings can be conditional on the SDK and the architecture,
+/// as in `KEY[sdk=macosx*][arch=x86_64] = VALUE`, where a trailing `*` matches
+/// any suffix. Settings with more conditions override those with fewer, which
+/// lets the slices of a universal binary use different values, and later
+/// settings override earlier ones. Settings with conditions other than `sdk`
+/// and `arch`, such as `config=Debug`, never apply.
+fn build_setting<'a>(settings: &'a str, key: &str, sdk: &str, arch: &str) -> Option<&'a str> {
+    let pattern_matches = |pattern: &str, value: &str| match pattern.strip_suffix('*') {
+        Some(prefix) => value.starts_with(prefix),
+        None => value == pattern,
+    };
+    let mut best: Option<(usize, &str)> = None;
+    for line in settings.lines() {
+        let Some(mut rest) = line.trim_start().strip_prefix(key) else {
+            continue;
+        };
+        let (mut conditions, mut matches) = (0, true);
+        while let Some(condition) = rest.strip_prefix('[') {
+            let Some((condition, after)) = condition.split_once(']') else {
+                matches = false;
+                break;
+            };
+            rest = after;
+            conditions += 1;
+            matches &= match condition.split_once('=') {
+                Some(("sdk", pattern)) => pattern_matches(pattern, sdk),
+                Some(("arch", pattern)) => pattern_matches(pattern, arch),
+                _ => false,
+            };
+        }
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        if matches && best.map_or(true, |(most, _)| conditions >= most) {
+            best = Some((conditions, value.trim()));
         }
-    } else {
-        min
     }
+    best.map(|(_, value)| value)
+}
+
+/// Convert to the "canonical" OS name used by LLVM:
+/// <https://github.com/llvm/llvm-project/blob/llvmorg-18.1.8/llvm/lib/TargetParser/Triple.cpp#L236-L282>
+fn llvm_os_name(os: &str) -> Result<&'static str, AppleTargetError> {
+    Ok(match os {
+        "macos" => "macosx",
+        "ios" => "ios",
+        "watchos" => "watchos",
+        "tvos" => "tvos",
+        "visionos" => "xros",
+        _ => return Err(AppleTargetError::UnknownOs(os.into())),
+    })
 }
 
 /// Generate the target triple that we need to pass to LLVM and/or Clang.
-fn llvm_target(os: &str, arch: Arch, abi: TargetAbi) -> StaticCow<str> {
+///
+/// The vendor is always `apple` for the built-in targets, but custom targets
+/// may use another one.
+fn llvm_target(
+    vendor: &str,
+    os: &str,
+    arch: Arch,
+    abi: TargetAbi,
+    version: OSVersion,
+) -> Result<StaticCow<str>, AppleTargetError> {
     // The target triple depends on the deployment target, and is required to
     // enable features such as cross-language LTO, and for picking the right
     // Mach-O commands.
     //
     // Certain optimizations also depend on the deployment target.
-    let (major, minor, patch) = deployment_target(os, arch, abi);
+    let (major, minor, patch) = version;
     let arch = arch.target_name();
-    // Convert to the "canonical" OS name used by LLVM:
-    // https://github.com/llvm/llvm-project/blob/llvmorg-18.1.8/llvm/lib/TargetParser/Triple.cpp#L236-L282
-    let os = match os {
-        "macos" => "macosx",
-        "ios" => "ios",
-        "watchos" => "watchos",
-        "tvos" => "tvos",
-        "visionos" => "xros",
-        _ => unreachable!("tried to get LLVM target OS for non-Apple platform"),
-    };
+    let os = llvm_os_name(os)?;
     let environment = match abi {
         TargetAbi::Normal => "",
         TargetAbi::MacCatalyst => "-macabi",
         TargetAbi::Simulator => "-simulator",
     };
-    format!("{arch}-apple-{os}{major}.{minor}.{patch}{environment}").into()
+    Ok(format!("{arch}-{vendor}-{os}{major}.{minor}.{patch}{environment}").into())
 }
 
+/// Extract the deployment target from an Apple LLVM target triple, such as
+/// `(16, 2, 0)` from `arm64-apple-ios16.2.0-simulator`.
+fn deployment_target_from_llvm_target(triple: &str) -> Option<OSVersion> {
+    let (_arch, rest) = triple.split_once('-')?;
+    // The vendor is usually `apple`, but custom targets may use another one.
+    let (_vendor, os) = rest.split_once('-')?;
+    let os = os.strip_suffix("-simulator").or_else(|| os.strip_suffix("-macabi")).unwrap_or(os);
+    // Both the canonical LLVM OS names and their alternate spellings.
+    let version = ["macosx", "macos", "ios", "tvos", "watchos", "xros", "visionos"]
+        .into_iter()
+        .find_map(|name| os.strip_prefix(name))?;
+    parse_version(version).ok()
+}
+
+/// Platform directories (in `Xcode.app/Contents/Developer/Platforms`) of Apple
+/// platforms other than macOS.
+const NON_MACOS_PLATFORM_DIRS: &[&str] = &[
+    "iPhoneOS.platform",
+    "iPhoneSimulator.platform",
+    "AppleTVOS.platform",
+    "AppleTVSimulator.platform",
+    "WatchOS.platform",
+    "WatchSimulator.platform",
+    "XROS.platform",
+    "XRSimulator.platform",
+];
+
+/// The Apple platform other than macOS whose SDK `SDKROOT` points to, if any.
+pub fn sdkroot_other_platform(sdkroot: &str) -> Option<&'static str> {
+    NON_MACOS_PLATFORM_DIRS
+        .iter()
+        .find(|platform| sdkroot.contains(*platform))
+        .map(|platform| platform.strip_suffix(".platform").unwrap())
+}
+
+/// The environment variable that makes the linker record zero instead of the
+/// modification time of object files, see `base`.
+const ZERO_AR_DATE: (&str, &str) = ("ZERO_AR_DATE", "1");
+
+/// Don't set `ZERO_AR_DATE` for the linker, for `-Zapple-keep-ar-date`, so
+/// that it records the modification times of object files, e.g. for external
+/// tooling that relies on them.
+pub fn keep_ar_date(target: &mut Target) {
+    target.options.link_env.to_mut().retain(|(name, value)| (&**name, &**value) != ZERO_AR_DATE);
+}
+
+/// Whether the linker is invoked with `ZERO_AR_DATE=1` for the target.
+fn zeroes_ar_date(target: &Target) -> bool {
+    target.link_env.iter().any(|(name, value)| (&**name, &**value) == ZERO_AR_DATE)
+}
+
+/// The environment variable that keeps `SDKROOT` in the linker environment
+/// when linking for macOS, see `link_env_remove`. The session tracks it, as
+/// the target depends on it.
+pub const KEEP_SDKROOT: &str = "RUSTC_KEEP_SDKROOT";
+
 fn link_env_remove(os: &'static str) -> StaticCow<[StaticCow<str>]> {
+    let mut env_remove = Vec::new();
     // Apple platforms only officially support macOS as a host for any compilation.
     //
     // If building for macOS, we go ahead and remove any erroneous environment state
     // that's only applicable to cross-OS compilation. Always leave anything for the
     // host OS alone though.
     if os == "macos" {
-        let mut env_remove = Vec::with_capacity(2);
-        // Remove the `SDKROOT` environment variable if it's clearly set for the wrong platform, which
-        // may occur when we're linking a custom build script while targeting iOS for example.
+        // Remove the `SDKROOT` environment variable if it's clearly set for the wrong platform,
+        // which may occur when we're linking a custom build script while targeting iOS for
+        // example.
+        //
+        // Users that deliberately set `SDKROOT` can opt out of this heuristic by setting
+        // `RUSTC_KEEP_SDKROOT=1`. With `-v`, the linker notes when this happens.
+        let keep_sdkroot = env::var(KEEP_SDKROOT).is_ok_and(|keep| keep == "1");
         if let Ok(sdkroot) = env::var("SDKROOT") {
-            if sdkroot.contains("iPhoneOS.platform")
-                || sdkroot.contains("iPhoneSimulator.platform")
-                || sdkroot.contains("AppleTVOS.platform")
-                || sdkroot.contains("AppleTVSimulator.platform")
-                || sdkroot.contains("WatchOS.platform")
-                || sdkroot.contains("WatchSimulator.platform")
-                || sdkroot.contains("XROS.platform")
-                || sdkroot.contains("XRSimulator.platform")
-            {
+            if !keep_sdkroot && sdkroot_other_platform(&sdkroot).is_some() {
                 env_remove.push("SDKROOT".into())
             }
         }
-        // Additionally, `IPHONEOS_DEPLOYMENT_TARGET` must not be set when using the Xcode linker at
-        // "/Applications/Xcode.app/Contents/Developer/Toolchains/XcodeDefault.xctoolchain/usr/bin/ld",
-        // although this is apparently ignored when using the linker at "/usr/bin/ld".
-        env_remove.push("IPHONEOS_DEPLOYMENT_TARGET".into());
-        env_remove.push("TVOS_DEPLOYMENT_TARGET".into());
-        env_remove.push("XROS_DEPLOYMENT_TARGET".into());
-        env_remove.into()
-    } else {
-        // Otherwise if cross-compiling for a different OS/SDK (including Mac Catalyst), remove any part
-        // of the linking environment that's wrong and reversed.
-        cvs!["MACOSX_DEPLOYMENT_TARGET"]
     }
+    // Remove the deployment targets of the other OSes, which may be left over from building for
+    // those, also when cross-compiling for a different OS/SDK (including Mac Catalyst).
+    //
+    // In particular, `IPHONEOS_DEPLOYMENT_TARGET` must not be set when using the Xcode linker at
+    // "/Applications/Xcode.app/Contents/Developer/Toolchains/XcodeDefault.xctoolchain/usr/bin/ld",
+    // although this is apparently ignored when using the linker at "/usr/bin/ld".
+    env_remove.extend(
+        DEPLOYMENT_TARGETS
+            .iter()
+            .filter(|&&(target_os, _, _)| target_os != os)
+            .flat_map(|(_, _, env_vars)| env_vars.iter().map(|&env_var| env_var.into())),
+    );
+    env_remove.into()
+}
+
+/// An error from `parse_version`.
+#[derive(Debug, PartialEq)]
+enum VersionParseError {
+    /// The version is the empty string.
+    Empty,
+    /// The version has more components than major, minor and patch.
+    TooManyComponents(usize),
+    /// A component of the version is not a number.
+    NotANumber { component: &'static str, value: String },
+    /// A component of the version is too large to be represented in Mach-O's
+    /// `LC_BUILD_VERSION`.
+    OutOfRange { component: &'static str, value: String, max: u64 },
+}
+
+impl fmt::Display for VersionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "the version is empty"),
+            Self::TooManyComponents(count) => write!(
+                f,
+                "the version has {count} components, but at most three (major.minor.patch) \
+                 are allowed"
+            ),
+            Self::NotANumber { component, value } => {
+                write!(f, "{component} version `{value}` is not a number")
+            }
+            Self::OutOfRange { component, value, max } => write!(
+                f,
+                "{component} version {value} exceeds the Mach-O LC_BUILD_VERSION limit of {max}"
+            ),
+        }
+    }
+}
+
+/// Parse a single component of an OS version, which must be at most `max`.
+fn parse_version_component<T: TryFrom<u64> + Into<u64>>(
+    component: &'static str,
+    value: &str,
+    max: T,
+) -> Result<T, VersionParseError> {
+    let max = max.into();
+    let out_of_range = || VersionParseError::OutOfRange { component, value: value.into(), max };
+    let parsed: u64 = value.parse().map_err(|err: ParseIntError| match err.kind() {
+        IntErrorKind::PosOverflow => out_of_range(),
+        _ => VersionParseError::NotANumber { component, value: value.into() },
+    })?;
+    T::try_from(parsed).map_err(|_| out_of_range())
+}
+
+/// Whether `version` is at least `major.minor`. Versions are compared
+/// numerically component-wise, so e.g. `10.9` is below `10.10`.
+fn version_at_least(version: OSVersion, major: u16, minor: u8) -> bool {
+    let (version_major, version_minor, _) = version;
+    (version_major, version_minor) >= (major, minor)
 }
 
 /// Parse an OS version triple (SDK version or deployment target).
-///
-/// The size of the returned numbers here are limited by Mach-O's
-/// `LC_BUILD_VERSION`.
-fn parse_version(version: &str) -> Result<(u16, u8, u8), ParseIntError> {
-    if let Some((major, minor)) = version.split_once('.') {
-        let major = major.parse()?;
-        if let Some((minor, patch)) = minor.split_once('.') {
-            Ok((major, minor.parse()?, patch.parse()?))
-        } else {
-            Ok((major, minor.parse()?, 0))
+fn parse_version(version: &str) -> Result<OSVersion, VersionParseError> {
+    let major = |major| parse_version_component("major", major, u16::MAX);
+    let minor = |minor| parse_version_component("minor", minor, u8::MAX);
+    let patch = |patch| parse_version_component("patch", patch, u8::MAX);
+    if version.is_empty() {
+        return Err(VersionParseError::Empty);
+    }
+    let components: Vec<&str> = version.split('.').collect();
+    match components[..] {
+        [major_str] => Ok((major(major_str)?, 0, 0)),
+        [major_str, minor_str] => Ok((major(major_str)?, minor(minor_str)?, 0)),
+        [major_str, minor_str, patch_str] => {
+            Ok((major(major_str)?, minor(minor_str)?, patch(patch_str)?))
         }
-    } else {
-        Ok((version.parse()?, 0, 0))
+        _ => Err(VersionParseError::TooManyComponents(components.len())),
     }
 }
@@ -1,10 +1,44 @@
-use super::parse_version;
+use std::path::Path;
+
+use super::{
+    arch_from_rust_triple, base, build_setting, check_deployment_target_file, dead_strip_dylibs,
+    deployment_target, deployment_target_env_vars, deployment_target_for_target,
+    deployment_target_from_llvm_target, deployment_target_min, disable_adhoc_codesign,
+    emit_bitcode_marker, find_deployment_target, host_macos_version, keep_ar_date,
+    ld_arch_is_downgraded, link_as_application_extension, link_env_remove, linked_platform_version,
+    llvm_os_name, llvm_target, mac_catalyst_version_from_macos, macho_platform, make_reproducible,
+    min_deployment_target, min_deployment_target_for_target, min_dylib_deployment_target,
+    object_sdk_version, override_deployment_target, parse_requested_deployment_target,
+    parse_sdk_version, parse_version, platform, platform_constant_name, platform_version,
+    plist_product_version, pre_link_args, require_sdk_version, resolve, sdk_platform_name,
+    sdk_root_version, sdk_settings_version, sdk_version, sdkroot_other_platform,
+    supports_dynamic_linking, supports_thread_local, target_arch_and_abi,
+    target_arch_from_llvm_target, use_cc_target, use_cc_version_min, use_legacy_version_min,
+    uses_build_version, validate, version_at_least, zero_sdk_version, zeroes_ar_date, zipper,
+    AppleTargetBuilder, AppleTargetError, Arch, OSVersion, TargetAbi, VersionParseError,
+    DEPLOYMENT_TARGETS, DEPLOYMENT_TARGET_FILE, NON_MACOS_PLATFORM_DIRS,
+};
+use crate::json::ToJson;
 use crate::spec::targets::{
-    aarch64_apple_darwin, aarch64_apple_ios_sim, aarch64_apple_visionos_sim,
-    aarch64_apple_watchos_sim, i686_apple_darwin, x86_64_apple_darwin, x86_64_apple_ios,
-    x86_64_apple_tvos, x86_64_apple_watchos_sim,
+    aarch64_apple_darwin, aarch64_apple_ios, aarch64_apple_ios_macabi, aarch64_apple_ios_sim,
+    aarch64_apple_tvos_sim, aarch64_apple_visionos_sim, aarch64_apple_watchos,
+    aarch64_apple_watchos_sim, arm64_32_apple_watchos, arm64e_apple_darwin, arm64e_apple_ios,
+    armv6_apple_ios, armv7k_apple_watchos, i386_apple_ios, i686_apple_darwin, x86_64_apple_darwin,
+    x86_64_apple_ios, x86_64_apple_ios_macabi, x86_64_apple_tvos, x86_64_apple_watchos_sim,
+};
+use crate::spec::{
+    load_builtin, Cc, FramePointer, LinkArgs, LinkerFlavor, Lld, SplitDebuginfo, StackProbeType,
+    Target, TARGETS,
 };
 
+/// The built-in Apple targets, along with their Rust target triples.
+fn builtin_apple_targets() -> impl Iterator<Item = (&'static str, Target)> {
+    TARGETS
+        .iter()
+        .filter_map(|&triple| Some((triple, load_builtin(triple)?)))
+        .filter(|(_, target)| target.is_like_osx)
+}
+
 #[test]
 fn simulator_targets_set_abi() {
     let all_sim_targets = [
@@ -38,16 +72,1753 @@ fn macos_link_environment_unmodified() {
             crate::spec::cvs![
                 "IPHONEOS_DEPLOYMENT_TARGET",
                 "TVOS_DEPLOYMENT_TARGET",
-                "XROS_DEPLOYMENT_TARGET"
+                "WATCHOS_DEPLOYMENT_TARGET",
+                "XROS_DEPLOYMENT_TARGET",
+                "VISIONOS_DEPLOYMENT_TARGET"
             ],
         );
     }
 }
 
+#[test]
+fn sdkroot_for_other_platforms_is_detected() {
+    let xcode = "/Applications/Xcode.app/Contents/Developer/Platforms";
+    for platform in [
+        "iPhoneOS",
+        "iPhoneSimulator",
+        "AppleTVOS",
+        "AppleTVSimulator",
+        "WatchOS",
+        "WatchSimulator",
+        "XROS",
+        "XRSimulator",
+    ] {
+        let sdkroot = format!("{xcode}/{platform}.platform/Developer/SDKs/{platform}.sdk");
+        assert_eq!(sdkroot_other_platform(&sdkroot), Some(platform), "{sdkroot}");
+    }
+
+    let sdkroot = format!("{xcode}/MacOSX.platform/Developer/SDKs/MacOSX.sdk");
+    assert_eq!(sdkroot_other_platform(&sdkroot), None);
+    assert_eq!(
+        sdkroot_other_platform("/Library/Developer/CommandLineTools/SDKs/MacOSX14.5.sdk"),
+        None
+    );
+    assert_eq!(sdkroot_other_platform(""), None);
+}
+
 #[test]
 fn test_parse_version() {
     assert_eq!(parse_version("10"), Ok((10, 0, 0)));
     assert_eq!(parse_version("10.12"), Ok((10, 12, 0)));
     assert_eq!(parse_version("10.12.6"), Ok((10, 12, 6)));
     assert_eq!(parse_version("9999.99.99"), Ok((9999, 99, 99)));
+    assert_eq!(parse_version("65535.255.255"), Ok((65535, 255, 255)));
+}
+
+#[test]
+fn parse_version_rejects_out_of_range_components() {
+    let out_of_range = |component, value: &str, max| VersionParseError::OutOfRange {
+        component,
+        value: value.into(),
+        max,
+    };
+    let err = parse_version("10.300").unwrap_err();
+    assert_eq!(err, out_of_range("minor", "300", 255));
+    assert_eq!(
+        err.to_string(),
+        "minor version 300 exceeds the Mach-O LC_BUILD_VERSION limit of 255"
+    );
+    assert_eq!(parse_version("70000"), Err(out_of_range("major", "70000", 65535)));
+    assert_eq!(parse_version("10.12.256"), Err(out_of_range("patch", "256", 255)));
+    // Too large to even be parsed.
+    assert_eq!(
+        parse_version("10.99999999999999999999"),
+        Err(out_of_range("minor", "99999999999999999999", 255))
+    );
+}
+
+#[test]
+fn parse_version_errors() {
+    assert_eq!(parse_version(""), Err(VersionParseError::Empty));
+    assert_eq!(parse_version("").unwrap_err().to_string(), "the version is empty");
+
+    assert_eq!(parse_version("10.12.6.1"), Err(VersionParseError::TooManyComponents(4)));
+    assert_eq!(
+        parse_version("1.2.3.4.5").unwrap_err().to_string(),
+        "the version has 5 components, but at most three (major.minor.patch) are allowed"
+    );
+
+    let not_a_number =
+        |component, value: &str| VersionParseError::NotANumber { component, value: value.into() };
+    assert_eq!(parse_version("10.x"), Err(not_a_number("minor", "x")));
+    assert_eq!(parse_version("ten"), Err(not_a_number("major", "ten")));
+    assert_eq!(parse_version("10.12."), Err(not_a_number("patch", "")));
+    assert_eq!(parse_version("-1"), Err(not_a_number("major", "-1")));
+    assert_eq!(parse_version("10.x").unwrap_err().to_string(), "minor version `x` is not a number");
+}
+
+#[test]
+fn watchos_dylib_support_depends_on_deployment_target() {
+    assert_eq!(min_dylib_deployment_target("watchos"), Some((6, 0, 0)));
+    assert_eq!(min_dylib_deployment_target("macos"), None);
+
+    assert!(!supports_dynamic_linking("watchos", TargetAbi::Normal, (5, 0, 0)));
+    assert!(supports_dynamic_linking("watchos", TargetAbi::Normal, (6, 0, 0)));
+    assert!(supports_dynamic_linking("watchos", TargetAbi::Normal, (9, 1, 0)));
+    assert!(supports_dynamic_linking("watchos", TargetAbi::Simulator, (5, 0, 0)));
+
+    // The default deployment target for watchOS devices is too low.
+    for target in [
+        aarch64_apple_watchos::target(),
+        arm64_32_apple_watchos::target(),
+        armv7k_apple_watchos::target(),
+    ] {
+        assert!(!target.dynamic_linking);
+    }
+}
+
+#[test]
+fn invalid_combinations_are_rejected() {
+    let invalid = [
+        ("tvos", Arch::Arm64, TargetAbi::MacCatalyst),
+        ("macos", Arch::X86_64, TargetAbi::MacCatalyst),
+        ("macos", Arch::Arm64, TargetAbi::Simulator),
+        ("ios", Arch::Arm64_32, TargetAbi::Normal),
+        ("macos", Arch::Armv7k, TargetAbi::Normal),
+        ("watchos", Arch::Armv7s, TargetAbi::Normal),
+        ("ios", Arch::X86_64, TargetAbi::Normal),
+        ("visionos", Arch::X86_64, TargetAbi::Simulator),
+    ];
+    for (os, arch, abi) in invalid {
+        assert!(
+            validate(os, arch, abi).is_err(),
+            "{os} {} {}",
+            arch.target_name(),
+            abi.target_abi()
+        );
+    }
+
+    assert_eq!(
+        validate("watchos", Arch::Arm64, TargetAbi::MacCatalyst).unwrap_err().to_string(),
+        "Mac Catalyst is only valid for iOS targets, not watchos",
+    );
+    assert_eq!(
+        validate("ios", Arch::Arm64_32, TargetAbi::Normal),
+        Err(AppleTargetError::UnsupportedArch { os: "ios".into(), arch: "arm64_32" }),
+    );
+}
+
+#[test]
+fn non_apple_targets_are_errors() {
+    assert_eq!(
+        base("linux", Arch::X86_64, TargetAbi::Normal).unwrap_err(),
+        AppleTargetError::UnknownOs("linux".into()),
+    );
+
+    let mut target = aarch64_apple_darwin::target();
+    target.os = "linux".into();
+    assert_eq!(
+        deployment_target_for_target(&target),
+        Err(AppleTargetError::UnknownOs("linux".into())),
+    );
+    target.os = "ios".into();
+    target.abi = "gnu".into();
+    assert_eq!(
+        deployment_target_for_target(&target),
+        Err(AppleTargetError::UnknownAbi("gnu".into())),
+    );
+}
+
+#[test]
+fn tvos_simulator_deployment_target_matches_llvm() {
+    let target = aarch64_apple_tvos_sim::target();
+    let (major, minor, patch) = deployment_target_for_target(&target).unwrap();
+    assert!((major, minor, patch) >= (14, 0, 0));
+    assert!(target.llvm_target.ends_with(&*format!("tvos{major}.{minor}.{patch}-simulator")));
+}
+
+#[test]
+fn arm64e_targets_link_arm64e() {
+    let target = arm64e_apple_darwin::target();
+    for flavor in [LinkerFlavor::Darwin(Cc::No, Lld::No), LinkerFlavor::Darwin(Cc::Yes, Lld::No)] {
+        let args = &target.pre_link_args[&flavor];
+        let arch = args.iter().position(|arg| arg == "-arch").unwrap();
+        assert_eq!(args[arch + 1], "arm64e");
+    }
+
+    let target = arm64e_apple_ios::target();
+    let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let arch = args.iter().position(|arg| arg == "-arch").unwrap();
+    assert_eq!(args[arch + 1], "arm64e");
+    // `-target` communicates the architecture to the C compiler.
+    let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, Lld::No)];
+    let triple = args.iter().position(|arg| arg == "-target").unwrap();
+    assert!(args[triple + 1].starts_with("arm64e-apple-ios"));
+}
+
+#[test]
+fn deployment_target_ceiling() {
+    let mut target = aarch64_apple_darwin::target();
+    let version = deployment_target_for_target(&target).unwrap();
+
+    target.apple_max_deployment_target = Some("99.0".into());
+    assert_eq!(deployment_target_for_target(&target), Ok(version));
+
+    target.apple_max_deployment_target = Some("10.12".into());
+    assert_eq!(
+        deployment_target_for_target(&target),
+        Err(AppleTargetError::DeploymentTargetTooHigh { version, max: (10, 12, 0) }),
+    );
+
+    target.apple_max_deployment_target = Some("latest".into());
+    assert_eq!(
+        deployment_target_for_target(&target),
+        Err(AppleTargetError::InvalidMaxDeploymentTarget("latest".into())),
+    );
+}
+
+#[test]
+fn frame_pointers_are_kept() {
+    // Every target follows the policy of its architecture, see
+    // `Arch::frame_pointer`.
+    for (triple, target) in builtin_apple_targets() {
+        let arch = arch_from_rust_triple(triple).unwrap();
+        assert_eq!(target.frame_pointer, arch.frame_pointer(), "{triple}");
+        assert_ne!(target.frame_pointer, FramePointer::MayOmit, "{triple}");
+    }
+    assert_eq!(aarch64_apple_darwin::target().frame_pointer, FramePointer::NonLeaf);
+    assert_eq!(arm64_32_apple_watchos::target().frame_pointer, FramePointer::NonLeaf);
+    assert_eq!(x86_64_apple_darwin::target().frame_pointer, FramePointer::Always);
+}
+
+#[test]
+fn deployment_target_from_llvm_triple() {
+    assert_eq!(deployment_target_from_llvm_target("arm64-apple-macosx11.0.0"), Some((11, 0, 0)));
+    assert_eq!(
+        deployment_target_from_llvm_target("arm64-apple-ios16.2.0-simulator"),
+        Some((16, 2, 0))
+    );
+    assert_eq!(deployment_target_from_llvm_target("x86_64-apple-ios13.1-macabi"), Some((13, 1, 0)));
+    assert_eq!(deployment_target_from_llvm_target("arm64-apple-xros1.0.0"), Some((1, 0, 0)));
+    assert_eq!(deployment_target_from_llvm_target("arm64-apple-macosx"), None);
+    assert_eq!(deployment_target_from_llvm_target("x86_64-unknown-linux-gnu"), None);
+
+    for target in [aarch64_apple_darwin::target(), aarch64_apple_tvos_sim::target()] {
+        assert_eq!(
+            deployment_target_from_llvm_target(&target.llvm_target),
+            deployment_target_for_target(&target).ok(),
+        );
+    }
+}
+
+#[test]
+fn x86_64h_ios_simulator() {
+    let (opts, llvm_target, arch) = base("ios", Arch::X86_64h, TargetAbi::Simulator).unwrap();
+    assert_eq!(arch, "x86_64");
+    assert_eq!(opts.cpu, "core-avx2");
+    assert!(llvm_target.starts_with("x86_64h-apple-ios"));
+    assert!(llvm_target.ends_with("-simulator"));
+
+    let args = &opts.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let start = args.iter().position(|arg| arg == "-platform_version").unwrap();
+    assert_eq!(args[start + 1], "ios-simulator");
+    let start = args.iter().position(|arg| arg == "-arch").unwrap();
+    assert_eq!(args[start + 1], "x86_64h");
+
+    assert_eq!(
+        validate("ios", Arch::X86_64h, TargetAbi::Normal),
+        Err(AppleTargetError::SimulatorOnlyArch { os: "ios".into(), arch: "x86_64h" }),
+    );
+}
+
+#[test]
+fn x86_64h_tvos_simulator() {
+    let (opts, llvm_target, arch) = base("tvos", Arch::X86_64h, TargetAbi::Simulator).unwrap();
+    assert_eq!(arch, "x86_64");
+    assert_eq!(opts.cpu, "core-avx2");
+    let version = deployment_target("tvos", Arch::X86_64h, TargetAbi::Simulator).unwrap();
+    let (major, minor, patch) = version;
+    assert_eq!(llvm_target, format!("x86_64h-apple-tvos{major}.{minor}.{patch}-simulator"));
+    assert_eq!(version, deployment_target("tvos", Arch::X86_64, TargetAbi::Simulator).unwrap());
+
+    let args = &opts.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let start = args.iter().position(|arg| arg == "-platform_version").unwrap();
+    assert_eq!(args[start + 1], "tvos-simulator");
+    let start = args.iter().position(|arg| arg == "-arch").unwrap();
+    assert_eq!(args[start + 1], "x86_64h");
+
+    assert_eq!(
+        validate("tvos", Arch::X86_64h, TargetAbi::Normal),
+        Err(AppleTargetError::SimulatorOnlyArch { os: "tvos".into(), arch: "x86_64h" }),
+    );
+    assert!(validate("watchos", Arch::X86_64h, TargetAbi::Simulator).is_err());
+}
+
+#[test]
+fn deployment_target_is_read_from_llvm_target() {
+    let mut target = aarch64_apple_darwin::target();
+    target.llvm_target = "arm64-apple-macosx12.3.0".into();
+    assert_eq!(deployment_target_for_target(&target), Ok((12, 3, 0)));
+}
+
+#[test]
+fn min_deployment_target_ignores_environment() {
+    assert_eq!(min_deployment_target_for_target(&aarch64_apple_darwin::target()), Ok((11, 0, 0)));
+    assert_eq!(min_deployment_target_for_target(&x86_64_apple_darwin::target()), Ok((10, 12, 0)));
+    assert_eq!(
+        min_deployment_target_for_target(&aarch64_apple_ios_macabi::target()),
+        Ok((14, 0, 0))
+    );
+    for target in [aarch64_apple_darwin::target(), aarch64_apple_tvos_sim::target()] {
+        let min = min_deployment_target_for_target(&target).unwrap();
+        assert!(deployment_target_for_target(&target).unwrap() >= min);
+    }
+}
+
+#[test]
+fn mac_catalyst_deployment_target_matches_llvm() {
+    assert_eq!(
+        min_deployment_target_for_target(&x86_64_apple_ios_macabi::target()),
+        Ok((13, 1, 0))
+    );
+
+    let target = aarch64_apple_ios_macabi::target();
+    let version = deployment_target_for_target(&target).unwrap();
+    assert!(version >= (14, 0, 0));
+    let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let start = args.iter().position(|arg| arg == "-platform_version").unwrap();
+    assert_eq!(args[start + 1], "mac-catalyst");
+    assert_eq!(linked_platform_version(&target).unwrap().0, version);
+}
+
+#[test]
+fn architectures_are_consistent() {
+    let mut names = Vec::new();
+    for &arch in Arch::all() {
+        let name = arch.target_name();
+        assert!(!names.contains(&name), "duplicate architecture {name}");
+        names.push(name);
+
+        match arch {
+            // ld64 doesn't understand i686.
+            Arch::I686 => assert_eq!(arch.ld_arch(), "i386"),
+            _ => assert_eq!(arch.ld_arch(), name),
+        }
+        assert!(["arm", "aarch64", "x86", "x86_64"].contains(&&*arch.target_arch()), "{name}");
+
+        // Every architecture must be usable on at least one OS.
+        assert!(!arch.supported_oses().is_empty(), "{name}");
+        for &os in arch.supported_oses() {
+            assert!(
+                [TargetAbi::Normal, TargetAbi::Simulator]
+                    .into_iter()
+                    .any(|abi| base(os, arch, abi).is_ok()),
+                "{name} on {os}"
+            );
+        }
+    }
+}
+
+#[test]
+fn i686_is_linked_as_i386() {
+    assert!(Arch::I686.ld_arch_is_downgraded());
+    assert!(!Arch::I386.ld_arch_is_downgraded());
+    assert!(ld_arch_is_downgraded(&i686_apple_darwin::target()));
+    assert!(!ld_arch_is_downgraded(&x86_64_apple_darwin::target()));
+    assert!(!ld_arch_is_downgraded(&arm64e_apple_darwin::target()));
+    assert!(!ld_arch_is_downgraded(&load_builtin("i686-unknown-linux-gnu").unwrap()));
+}
+
+#[test]
+fn build_settings_are_parsed() {
+    let settings = "\
+// Deployment targets
+MACOSX_DEPLOYMENT_TARGET = 11.0
+// MACOSX_DEPLOYMENT_TARGET = 10.9
+IPHONEOS_DEPLOYMENT_TARGET=15.0
+IPHONEOS_DEPLOYMENT_TARGET = 16.2
+";
+    assert_eq!(
+        build_setting(settings, "MACOSX_DEPLOYMENT_TARGET", "macosx", "arm64"),
+        Some("11.0")
+    );
+    assert_eq!(
+        build_setting(settings, "IPHONEOS_DEPLOYMENT_TARGET", "iphoneos", "arm64"),
+        Some("16.2")
+    );
+    assert_eq!(build_setting(settings, "TVOS_DEPLOYMENT_TARGET", "appletvos", "arm64"), None);
+    assert_eq!(build_setting("", "MACOSX_DEPLOYMENT_TARGET", "macosx", "arm64"), None);
+}
+
+#[test]
+fn build_version_load_command_cutoffs() {
+    assert!(!uses_build_version("macos", (10, 13, 6)));
+    assert!(uses_build_version("macos", (10, 14, 0)));
+    assert!(!uses_build_version("ios", (11, 4, 0)));
+    assert!(uses_build_version("ios", (12, 0, 0)));
+    assert!(!uses_build_version("tvos", (10, 0, 0)));
+    assert!(uses_build_version("tvos", (12, 0, 0)));
+    assert!(uses_build_version("watchos", (5, 0, 0)));
+    assert!(uses_build_version("visionos", (1, 0, 0)));
+    assert!(!uses_build_version("linux", (99, 0, 0)));
+}
+
+#[test]
+fn stack_probes_can_be_overridden() {
+    for &arch in Arch::all() {
+        let expected = match arch {
+            Arch::Armv6 | Arch::Armv7k | Arch::Armv7s => StackProbeType::None,
+            _ => StackProbeType::Inline,
+        };
+        assert_eq!(arch.stack_probes(), expected, "{}", arch.target_name());
+    }
+
+    let mut json = x86_64_apple_darwin::target().to_json();
+    json.as_object_mut()
+        .unwrap()
+        .insert("stack-probes".into(), serde_json::json!({ "kind": "call" }));
+    let (target, _) = Target::from_json(json).unwrap();
+    assert_eq!(target.stack_probes, StackProbeType::Call);
+}
+
+#[test]
+fn sdk_version_is_at_least_deployment_target() {
+    for target in [
+        aarch64_apple_darwin::target(),
+        aarch64_apple_ios_macabi::target(),
+        aarch64_apple_tvos_sim::target(),
+        aarch64_apple_visionos_sim::target(),
+        arm64_32_apple_watchos::target(),
+        x86_64_apple_ios::target(),
+    ] {
+        let (min_version, sdk_version) = linked_platform_version(&target).unwrap();
+        assert!(sdk_version >= min_version, "{}", target.llvm_target);
+        assert_eq!(min_version, deployment_target_for_target(&target).unwrap());
+    }
+}
+
+#[test]
+fn armv6_ios_target() {
+    let target = armv6_apple_ios::target();
+    assert_eq!(target.arch, "arm");
+    assert_eq!(target.cpu, "arm1176jzf-s");
+    assert_eq!(target.stack_probes, StackProbeType::None);
+    let (major, minor, patch) = deployment_target_for_target(&target).unwrap();
+    assert!((major, minor, patch) >= (3, 0, 0));
+    assert_eq!(target.llvm_target, format!("armv6-apple-ios{major}.{minor}.{patch}"));
+
+    let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let arch = args.iter().position(|arg| arg == "-arch").unwrap();
+    assert_eq!(args[arch + 1], "armv6");
+}
+
+#[test]
+fn builder_defaults_match_base() {
+    let built = AppleTargetBuilder::new("ios", Arch::Arm64, TargetAbi::Simulator).unwrap().build();
+    assert_eq!(built, base("ios", Arch::Arm64, TargetAbi::Simulator).unwrap());
+
+    let (opts, _, _) = AppleTargetBuilder::new("macos", Arch::X86_64, TargetAbi::Normal)
+        .unwrap()
+        .max_atomic_width(128)
+        .build();
+    assert_eq!(opts.max_atomic_width, Some(128));
+}
+
+#[test]
+fn visionos_deployment_target_env_vars() {
+    let vars = ["XROS_DEPLOYMENT_TARGET", "VISIONOS_DEPLOYMENT_TARGET"];
+    let lookup = |env: &[(&str, &str)]| {
+        find_deployment_target(&vars, |var| {
+            env.iter().find(|(name, _)| *name == var).map(|(_, value)| value.to_string())
+        })
+    };
+    assert_eq!(lookup(&[]), None);
+    assert_eq!(lookup(&[("XROS_DEPLOYMENT_TARGET", "1.1")]).as_deref(), Some("1.1"));
+    assert_eq!(lookup(&[("VISIONOS_DEPLOYMENT_TARGET", "1.2")]).as_deref(), Some("1.2"));
+    assert_eq!(
+        lookup(&[("VISIONOS_DEPLOYMENT_TARGET", "1.2"), ("XROS_DEPLOYMENT_TARGET", "1.1")])
+            .as_deref(),
+        Some("1.1")
+    );
+}
+
+#[test]
+fn every_apple_target_has_a_platform() {
+    for (_, target) in builtin_apple_targets() {
+        assert!(platform(&target).is_some(), "{} has no Mach-O platform", target.llvm_target);
+    }
+}
+
+#[test]
+fn application_extensions_opt_in() {
+    let linked_as_extension = |target: &Target, lld| {
+        let ld_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, lld)];
+        let cc_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, lld)];
+        match (
+            ld_args.iter().any(|arg| arg == "-application_extension"),
+            cc_args.iter().any(|arg| arg == "-fapplication-extension"),
+        ) {
+            (true, true) => true,
+            (false, false) => false,
+            _ => panic!("inconsistent linker arguments"),
+        }
+    };
+
+    let mut target = aarch64_apple_ios::target();
+    assert!(!linked_as_extension(&target, Lld::No));
+    link_as_application_extension(&mut target);
+    assert!(linked_as_extension(&target, Lld::No));
+    assert!(linked_as_extension(&target, Lld::Yes));
+}
+
+#[test]
+fn macos_aarch64_floors() {
+    assert_eq!(min_deployment_target("macos", Arch::Arm64, TargetAbi::Normal), Ok((11, 0, 0)));
+    assert_eq!(min_deployment_target("macos", Arch::Arm64e, TargetAbi::Normal), Ok((12, 0, 0)));
+    assert_eq!(min_deployment_target("macos", Arch::X86_64, TargetAbi::Normal), Ok((10, 12, 0)));
+    assert_eq!(min_deployment_target_for_target(&arm64e_apple_darwin::target()), Ok((12, 0, 0)));
+}
+
+#[test]
+fn all_deployment_target_env_vars_listed() {
+    let env_vars = deployment_target_env_vars();
+    for var in ["MACOSX_DEPLOYMENT_TARGET", "IPHONEOS_DEPLOYMENT_TARGET", "XROS_DEPLOYMENT_TARGET"]
+    {
+        assert!(env_vars.contains(&var), "{var} is missing");
+    }
+    assert!(env_vars.iter().all(|var| var.ends_with("_DEPLOYMENT_TARGET")));
+}
+
+#[test]
+fn cc_target_on_macos_opt_in() {
+    let cc_args =
+        |target: &Target, lld| target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, lld)].clone();
+
+    let mut target = aarch64_apple_darwin::target();
+    let args = cc_args(&target, Lld::No);
+    assert!(!args.iter().any(|arg| arg == "-target"));
+    assert!(args.iter().any(|arg| arg.starts_with("-mmacosx-version-min=")));
+
+    link_as_application_extension(&mut target);
+    use_cc_target(&mut target).unwrap();
+    for lld in [Lld::No, Lld::Yes] {
+        let args = cc_args(&target, lld);
+        let i = args.iter().position(|arg| arg == "-target").unwrap();
+        assert_eq!(args[i + 1], target.llvm_target);
+        assert!(!args.iter().any(|arg| arg == "-arch" || arg.starts_with("-mmacosx-version-min=")));
+        assert!(args.iter().any(|arg| arg == "-fapplication-extension"));
+    }
+
+    // Other OSes always use `-target`.
+    let mut target = aarch64_apple_ios_macabi::target();
+    let args = cc_args(&target, Lld::No);
+    use_cc_target(&mut target).unwrap();
+    assert_eq!(cc_args(&target, Lld::No), args);
+
+    // Custom targets may pass other arguments to CC.
+    let mut target = aarch64_apple_darwin::target();
+    target.options.pre_link_args.clear();
+    assert_eq!(
+        use_cc_target(&mut target),
+        Err(AppleTargetError::UnknownCcTargetArgs(target.llvm_target.to_string()))
+    );
+}
+
+#[test]
+fn mac_catalyst_is_ios_only() {
+    for os in ["tvos", "watchos", "visionos"] {
+        let err = base(os, Arch::Arm64, TargetAbi::MacCatalyst).unwrap_err();
+        assert_eq!(err, AppleTargetError::UnsupportedAbi { os: os.into(), abi: "macabi" });
+        assert_eq!(
+            err.to_string(),
+            format!("Mac Catalyst is only valid for iOS targets, not {os}")
+        );
+    }
+    assert!(base("ios", Arch::Arm64, TargetAbi::MacCatalyst).is_ok());
+}
+
+#[test]
+fn sdk_platform_names() {
+    assert_eq!(sdk_platform_name(&aarch64_apple_darwin::target()), Some("MacOSX"));
+    assert_eq!(sdk_platform_name(&aarch64_apple_ios_macabi::target()), Some("MacOSX"));
+    assert_eq!(sdk_platform_name(&aarch64_apple_ios::target()), Some("iPhoneOS"));
+    assert_eq!(sdk_platform_name(&aarch64_apple_ios_sim::target()), Some("iPhoneSimulator"));
+    assert_eq!(sdk_platform_name(&aarch64_apple_watchos_sim::target()), Some("WatchSimulator"));
+    assert_eq!(sdk_platform_name(&load_builtin("x86_64-unknown-linux-gnu").unwrap()), None);
+
+    // Every built-in target maps to a platform directory that `link_env_remove`
+    // knows about.
+    for target in TARGETS.iter().filter_map(|triple| load_builtin(triple)) {
+        let Some(name) = sdk_platform_name(&target) else { continue };
+        assert!(
+            name == "MacOSX" || NON_MACOS_PLATFORM_DIRS.contains(&&*format!("{name}.platform")),
+            "{}: {name}",
+            target.llvm_target
+        );
+    }
+}
+
+#[test]
+fn split_debuginfo_can_default_to_unpacked() {
+    assert_eq!(aarch64_apple_darwin::target().split_debuginfo, SplitDebuginfo::Packed);
+
+    let mut json = aarch64_apple_darwin::target().to_json();
+    json.as_object_mut().unwrap().insert("split-debuginfo".into(), "unpacked".into());
+    let (target, _) = Target::from_json(json).unwrap();
+    assert_eq!(target.split_debuginfo, SplitDebuginfo::Unpacked);
+    assert!(target.supported_split_debuginfo.contains(&SplitDebuginfo::Unpacked));
+}
+
+#[test]
+fn version_comparisons() {
+    assert!(version_at_least((11, 0, 0), 11, 0));
+    assert!(version_at_least((11, 0, 1), 11, 0));
+    assert!(version_at_least((11, 1, 0), 11, 0));
+    assert!(version_at_least((12, 0, 0), 11, 5));
+    assert!(!version_at_least((10, 15, 7), 11, 0));
+    assert!(!version_at_least((11, 0, 0), 11, 1));
+    assert!(version_at_least((10, 10, 0), 10, 9));
+    assert!(!version_at_least((10, 9, 5), 10, 10));
+}
+
+#[test]
+fn target_abi_round_trips() {
+    for abi in [TargetAbi::Normal, TargetAbi::Simulator, TargetAbi::MacCatalyst] {
+        assert!(TargetAbi::from_abi_str(&abi.to_string()) == Some(abi), "{abi}");
+    }
+    assert_eq!(TargetAbi::MacCatalyst.to_string(), "macabi");
+    assert!(TargetAbi::from_abi_str("gnu").is_none());
+
+    let mut target = aarch64_apple_ios::target();
+    target.abi = "gnu".into();
+    assert_eq!(
+        deployment_target_for_target(&target),
+        Err(AppleTargetError::UnknownAbi("gnu".into()))
+    );
+}
+
+#[test]
+fn dead_strip_dylibs_opt_in() {
+    let has_arg = |target: &Target, cc, arg| {
+        target.pre_link_args[&LinkerFlavor::Darwin(cc, Lld::No)].iter().any(|a| a == arg)
+    };
+
+    let mut target = aarch64_apple_ios::target();
+    assert!(!has_arg(&target, Cc::No, "-dead_strip_dylibs"));
+    assert!(!has_arg(&target, Cc::Yes, "-Wl,-dead_strip_dylibs"));
+
+    dead_strip_dylibs(&mut target);
+    assert!(has_arg(&target, Cc::No, "-dead_strip_dylibs"));
+    assert!(has_arg(&target, Cc::Yes, "-Wl,-dead_strip_dylibs"));
+}
+
+#[test]
+fn bitcode_marker_opt_in() {
+    let args = |target: &Target, cc| -> Vec<String> {
+        target.pre_link_args[&LinkerFlavor::Darwin(cc, Lld::No)]
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    };
+    let ld_args = ["-bitcode_bundle", "-bitcode_process_mode", "marker"];
+    let cc_args = ["-Wl,-bitcode_bundle", "-Wl,-bitcode_process_mode,marker"];
+
+    let mut target = aarch64_apple_ios::target();
+    assert!(!args(&target, Cc::No).iter().any(|a| a == "-bitcode_bundle"));
+    assert!(!args(&target, Cc::Yes).iter().any(|a| a == "-Wl,-bitcode_bundle"));
+
+    emit_bitcode_marker(&mut target);
+    assert!(args(&target, Cc::No).windows(3).any(|w| w == ld_args));
+    assert!(args(&target, Cc::Yes).windows(2).any(|w| w == cc_args));
+}
+
+#[test]
+fn zippered_links_for_mac_catalyst_too() {
+    let args = |target: &Target, cc| -> Vec<String> {
+        target.pre_link_args[&LinkerFlavor::Darwin(cc, Lld::No)]
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    };
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "10.12").unwrap();
+    zipper(&mut target).unwrap();
+
+    let ld_args = args(&target, Cc::No);
+    let platforms: Vec<_> =
+        ld_args.windows(2).filter(|w| w[0] == "-platform_version").map(|w| w[1].as_str()).collect();
+    assert_eq!(platforms, ["macos", "mac-catalyst"]);
+    // The deployment target predates Mac Catalyst.
+    assert!(ld_args.windows(3).any(|w| w == ["-platform_version", "mac-catalyst", "13.1.0"]));
+
+    let cc_args = args(&target, Cc::Yes);
+    assert!(
+        cc_args
+            .windows(2)
+            .any(|w| w == ["-darwin-target-variant", "x86_64-apple-ios13.1.0-macabi"])
+    );
+}
+
+#[test]
+fn only_macos_can_be_zippered() {
+    let err = zipper(&mut aarch64_apple_ios::target()).unwrap_err();
+    assert_eq!(err.to_string(), "only macOS binaries can be zippered, not ios binaries");
+    assert!(zipper(&mut aarch64_apple_ios_macabi::target()).is_err());
+    // There is no Mac Catalyst slice for `i686`.
+    assert!(zipper(&mut i686_apple_darwin::target()).is_err());
+}
+
+#[test]
+fn unknown_sdk_version_can_be_zero() {
+    let sdk_version = |target: &Target| {
+        let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+        let start = args.iter().position(|a| a == "-platform_version").unwrap();
+        args[start + 3].to_string()
+    };
+
+    // By default, the deployment target is repeated.
+    let mut target = aarch64_apple_darwin::target();
+    override_deployment_target(&mut target, "11.0").unwrap();
+    assert_eq!(sdk_version(&target), "11.0.0");
+    zero_sdk_version(&mut target);
+    assert_eq!(sdk_version(&target), "0.0.0");
+    // A pinned SDK version is still used.
+    make_reproducible(&mut target, "14.2", true).unwrap();
+    assert_eq!(sdk_version(&target), "14.2.0");
+}
+
+#[test]
+fn function_sections_are_disabled() {
+    for (_, target) in builtin_apple_targets() {
+        assert!(!target.function_sections, "{}", target.llvm_target);
+    }
+}
+
+#[test]
+fn adhoc_codesign_opt_out() {
+    let has_arg = |target: &Target, cc, arg| {
+        target.pre_link_args[&LinkerFlavor::Darwin(cc, Lld::No)].iter().any(|a| a == arg)
+    };
+
+    let mut target = aarch64_apple_darwin::target();
+    assert!(!has_arg(&target, Cc::No, "-no_adhoc_codesign"));
+    assert!(!has_arg(&target, Cc::Yes, "-Wl,-no_adhoc_codesign"));
+
+    disable_adhoc_codesign(&mut target);
+    assert!(has_arg(&target, Cc::No, "-no_adhoc_codesign"));
+    assert!(has_arg(&target, Cc::Yes, "-Wl,-no_adhoc_codesign"));
+}
+
+#[test]
+fn zero_ar_date_can_be_kept() {
+    let mut target = x86_64_apple_darwin::target();
+    assert_eq!(&*target.link_env, [("ZERO_AR_DATE".into(), "1".into())]);
+    keep_ar_date(&mut target);
+    assert!(!zeroes_ar_date(&target));
+
+    // Reproducible builds always zero the dates.
+    make_reproducible(&mut target, "14.0", true).unwrap();
+    assert!(zeroes_ar_date(&target));
+}
+
+#[test]
+fn deployment_targets_of_other_oses_are_removed() {
+    let removed = link_env_remove("visionos");
+    assert!(removed.contains(&"IPHONEOS_DEPLOYMENT_TARGET".into()));
+    assert!(removed.contains(&"MACOSX_DEPLOYMENT_TARGET".into()));
+    assert!(!removed.contains(&"XROS_DEPLOYMENT_TARGET".into()));
+    assert!(!removed.contains(&"VISIONOS_DEPLOYMENT_TARGET".into()));
+
+    // Also applies to Mac Catalyst, which uses the iOS deployment target.
+    let removed = link_env_remove("ios");
+    assert!(removed.contains(&"MACOSX_DEPLOYMENT_TARGET".into()));
+    assert!(!removed.contains(&"IPHONEOS_DEPLOYMENT_TARGET".into()));
+
+    // Every other OS is covered when building for macOS.
+    let removed = link_env_remove("macos");
+    for var in [
+        "IPHONEOS_DEPLOYMENT_TARGET",
+        "TVOS_DEPLOYMENT_TARGET",
+        "WATCHOS_DEPLOYMENT_TARGET",
+        "XROS_DEPLOYMENT_TARGET",
+        "VISIONOS_DEPLOYMENT_TARGET",
+    ] {
+        assert!(removed.contains(&var.into()), "{var}");
+    }
+    assert!(!removed.contains(&"MACOSX_DEPLOYMENT_TARGET".into()));
+}
+
+#[test]
+fn deployment_target_table_is_consistent() {
+    for &(os, os_min, env_vars) in DEPLOYMENT_TARGETS {
+        // Every OS reads at least one environment variable.
+        assert!(!env_vars.is_empty(), "{os}");
+        // The table holds the floor of the OS, which is at most the floor of
+        // any specific target on it, except for the old 32-bit ARM devices.
+        let mut found_target = false;
+        for &arch in Arch::all().iter().filter(|arch| arch.supported_oses().contains(&os)) {
+            for abi in [TargetAbi::Normal, TargetAbi::Simulator, TargetAbi::MacCatalyst] {
+                if validate(os, arch, abi).is_ok() {
+                    found_target = true;
+                    let min = min_deployment_target(os, arch, abi).unwrap();
+                    let old_device =
+                        matches!((os, arch), ("ios", Arch::Armv6) | ("watchos", Arch::Armv7k));
+                    assert!(min >= os_min || old_device, "{os} {min:?}");
+                }
+            }
+        }
+        assert!(found_target, "{os} has no targets");
+    }
+}
+
+#[test]
+fn legacy_version_min_lowers_the_linked_version() {
+    let platform_version = |target: &Target| {
+        let args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+        let i = args.iter().position(|arg| arg == "-platform_version").unwrap();
+        (args[i + 1].to_string(), parse_version(&args[i + 2]).unwrap())
+    };
+    let cc_args =
+        |target: &Target| target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, Lld::No)].clone();
+
+    for (mut target, version) in [
+        (aarch64_apple_darwin::target(), "14.0"),
+        (arm64e_apple_ios::target(), "17.0"),
+        (arm64_32_apple_watchos::target(), "10.0"),
+    ] {
+        override_deployment_target(&mut target, version).unwrap();
+        let os = target.os.clone();
+        let (_, version) = platform_version(&target);
+        assert!(uses_build_version(&os, version), "{os}");
+
+        let llvm_target = target.llvm_target.clone();
+        use_legacy_version_min(&mut target).unwrap();
+        let (platform, version) = platform_version(&target);
+        assert_eq!(platform, os);
+        assert!(!uses_build_version(&os, version), "{os}");
+        // Only the linker is told about the lower version.
+        assert_eq!(target.llvm_target, llvm_target);
+        assert_eq!(
+            deployment_target_for_target(&target).ok(),
+            deployment_target_from_llvm_target(&llvm_target)
+        );
+    }
+
+    // Also applies to CC.
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "14.0").unwrap();
+    use_legacy_version_min(&mut target).unwrap();
+    assert!(cc_args(&target).iter().any(|arg| arg == "-mmacosx-version-min=10.13.0"));
+    let mut target = arm64e_apple_ios::target();
+    override_deployment_target(&mut target, "17.0").unwrap();
+    use_legacy_version_min(&mut target).unwrap();
+    assert!(cc_args(&target).iter().any(|arg| arg == "arm64e-apple-ios11.0.0"));
+    assert_ne!(target.llvm_target, "arm64e-apple-ios11.0.0");
+
+    // No-op when the deployment target is already low enough.
+    for version in ["10.12", "10.13.6"] {
+        let mut target = x86_64_apple_darwin::target();
+        override_deployment_target(&mut target, version).unwrap();
+        let linked = platform_version(&target);
+        use_legacy_version_min(&mut target).unwrap();
+        assert_eq!(platform_version(&target), linked, "{version}");
+    }
+
+    for mut target in [aarch64_apple_ios_macabi::target(), aarch64_apple_visionos_sim::target()] {
+        assert!(use_legacy_version_min(&mut target).is_err(), "{}", target.llvm_target);
+    }
+    let err = use_legacy_version_min(&mut aarch64_apple_ios_macabi::target()).unwrap_err();
+    assert_eq!(err.to_string(), "Mac Catalyst has no legacy `LC_VERSION_MIN_*` load command");
+}
+
+#[test]
+fn x86_simulator_cpus() {
+    assert_eq!(i386_apple_ios::target().cpu, "yonah");
+    assert_eq!(i686_apple_darwin::target().cpu, "penryn");
+    assert_eq!(x86_64_apple_ios::target().cpu, "penryn");
+    assert_eq!(x86_64_apple_darwin::target().cpu, "penryn");
+}
+
+#[test]
+fn mac_catalyst_versions_from_macos() {
+    assert_eq!(mac_catalyst_version_from_macos((10, 14, 0)), (13, 1, 0));
+    assert_eq!(mac_catalyst_version_from_macos((10, 15, 0)), (13, 1, 0));
+    assert_eq!(mac_catalyst_version_from_macos((11, 0, 0)), (14, 2, 0));
+    assert_eq!(mac_catalyst_version_from_macos((11, 5, 0)), (14, 7, 0));
+    assert_eq!(mac_catalyst_version_from_macos((12, 0, 0)), (15, 0, 0));
+    assert_eq!(mac_catalyst_version_from_macos((12, 3, 1)), (15, 4, 0));
+    assert_eq!(mac_catalyst_version_from_macos((13, 0, 0)), (16, 1, 0));
+    assert_eq!(mac_catalyst_version_from_macos((14, 0, 0)), (17, 0, 0));
+    assert_eq!(mac_catalyst_version_from_macos((15, 2, 0)), (18, 2, 0));
+}
+
+#[test]
+fn pre_link_args_per_flavor() {
+    let args = |target: &Target, cc, lld| {
+        target.pre_link_args[&LinkerFlavor::Darwin(cc, lld)]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect::<Vec<_>>()
+    };
+    let version = |target: &Target| {
+        let (major, minor, patch) = deployment_target_for_target(target).unwrap();
+        format!("{major}.{minor}.{patch}")
+    };
+
+    let target = x86_64_apple_darwin::target();
+    let v = version(&target);
+    assert_eq!(target.pre_link_args.len(), 4);
+    for lld in [Lld::No, Lld::Yes] {
+        assert_eq!(
+            args(&target, Cc::No, lld),
+            ["-arch", "x86_64", "-platform_version", "macos", &v, &v]
+        );
+        assert_eq!(
+            args(&target, Cc::Yes, lld),
+            ["-arch", "x86_64", &format!("-mmacosx-version-min={v}")]
+        );
+    }
+
+    let target = aarch64_apple_ios::target();
+    let v = version(&target);
+    assert_eq!(target.pre_link_args.len(), 4);
+    for lld in [Lld::No, Lld::Yes] {
+        assert_eq!(
+            args(&target, Cc::No, lld),
+            ["-arch", "arm64", "-platform_version", "ios", &v, &v]
+        );
+        assert_eq!(args(&target, Cc::Yes, lld), ["-target", &*target.llvm_target]);
+    }
+}
+
+#[test]
+fn arm64e_deployment_target_floor() {
+    for target in [arm64e_apple_darwin::target(), arm64e_apple_ios::target()] {
+        let min = min_deployment_target_for_target(&target).unwrap();
+        assert!(deployment_target_for_target(&target).unwrap() >= min);
+    }
+
+    let mut target = arm64e_apple_darwin::target();
+    target.llvm_target = "arm64e-apple-macosx11.0.0".into();
+    let err = deployment_target_for_target(&target).unwrap_err();
+    assert_eq!(
+        err,
+        AppleTargetError::DeploymentTargetTooLow {
+            arch: "arm64e",
+            version: (11, 0, 0),
+            min: (12, 0, 0)
+        }
+    );
+    assert_eq!(
+        err.to_string(),
+        "deployment target 11.0.0 is lower than the minimum deployment target 12.0.0 of `arm64e`"
+    );
+}
+
+#[test]
+fn resolve_matches_built_targets() {
+    let targets = [
+        ("macos", Arch::Arm64, TargetAbi::Normal, aarch64_apple_darwin::target()),
+        ("ios", Arch::Arm64, TargetAbi::MacCatalyst, aarch64_apple_ios_macabi::target()),
+        ("tvos", Arch::Arm64, TargetAbi::Simulator, aarch64_apple_tvos_sim::target()),
+        ("watchos", Arch::Arm64_32, TargetAbi::Normal, arm64_32_apple_watchos::target()),
+    ];
+    for (os, arch, abi, target) in targets {
+        let info = resolve(os, arch, abi).unwrap();
+        assert_eq!(info.llvm_target, target.llvm_target);
+        assert_eq!(info.deployment_target, deployment_target_for_target(&target).unwrap());
+        assert_eq!(Some(info.platform), platform(&target));
+        let (sdk_major, sdk_minor) = sdk_version(info.platform).unwrap();
+        assert_eq!(info.sdk_version, (sdk_major, sdk_minor, 0).max(info.deployment_target));
+    }
+    assert!(resolve("macos", Arch::Arm64, TargetAbi::Simulator).is_err());
+}
+
+#[test]
+fn visionos_llvm_targets() {
+    assert_eq!(
+        llvm_target("apple", "visionos", Arch::Arm64, TargetAbi::Normal, (1, 0, 0)).unwrap(),
+        "arm64-apple-xros1.0.0"
+    );
+    assert_eq!(
+        llvm_target("apple", "visionos", Arch::Arm64, TargetAbi::Simulator, (1, 2, 3)).unwrap(),
+        "arm64-apple-xros1.2.3-simulator"
+    );
+
+    let target = aarch64_apple_visionos_sim::target();
+    assert!(target.llvm_target.starts_with("arm64-apple-xros"), "{}", target.llvm_target);
+    assert!(target.llvm_target.ends_with("-simulator"), "{}", target.llvm_target);
+}
+
+#[test]
+fn pointer_widths() {
+    for &arch in Arch::all() {
+        let expected = match arch {
+            Arch::Armv6 | Arch::Armv7k | Arch::Armv7s | Arch::I386 | Arch::I686 => 32,
+            // ILP32 on a 64-bit architecture.
+            Arch::Arm64_32 => 32,
+            Arch::Arm64 | Arch::Arm64e | Arch::X86_64 | Arch::X86_64h => 64,
+        };
+        assert_eq!(arch.pointer_width(), expected, "{}", arch.target_name());
+        assert_eq!(arch.is_32_bit(), expected == 32, "{}", arch.target_name());
+    }
+
+    for (_, target) in builtin_apple_targets() {
+        let arch = target_arch_from_llvm_target(&target).unwrap();
+        assert_eq!(target.pointer_width, arch.pointer_width(), "{}", target.llvm_target);
+    }
+}
+
+#[test]
+fn archive_format_defaults_to_darwin() {
+    for (_, target) in builtin_apple_targets() {
+        assert_eq!(target.archive_format, "darwin", "{}", target.llvm_target);
+    }
+}
+
+#[test]
+fn watchos_arm64_device_target() {
+    assert_eq!(Arch::Arm64.target_cpu("watchos", TargetAbi::Normal), "apple-a16");
+    assert_eq!(Arch::Arm64_32.target_cpu("watchos", TargetAbi::Normal), "apple-s4");
+    assert_eq!(Arch::Arm64.target_cpu("watchos", TargetAbi::Simulator), "apple-m1");
+
+    let target = aarch64_apple_watchos::target();
+    assert!(target.llvm_target.starts_with("arm64-apple-watchos"), "{}", target.llvm_target);
+    assert_eq!(target.cpu, "apple-a16");
+}
+
+#[test]
+fn llvm_os_names() {
+    assert_eq!(llvm_os_name("macos").unwrap(), "macosx");
+    assert_eq!(llvm_os_name("ios").unwrap(), "ios");
+    assert_eq!(llvm_os_name("tvos").unwrap(), "tvos");
+    assert_eq!(llvm_os_name("watchos").unwrap(), "watchos");
+    // LLVM uses the internal name of visionOS.
+    assert_eq!(llvm_os_name("visionos").unwrap(), "xros");
+    assert!(llvm_os_name("linux").is_err());
+}
+
+#[test]
+fn sdk_version_is_never_lower_than_deployment_target() {
+    let versions = |args: &LinkArgs| {
+        let args = &args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+        let start = args.iter().position(|a| a == "-platform_version").unwrap();
+        (parse_version(&args[start + 2]).unwrap(), parse_version(&args[start + 3]).unwrap())
+    };
+
+    for (os, arch, abi) in [
+        ("macos", Arch::Arm64, TargetAbi::Normal),
+        ("ios", Arch::Arm64, TargetAbi::MacCatalyst),
+        ("watchos", Arch::Arm64, TargetAbi::Normal),
+        ("visionos", Arch::Arm64, TargetAbi::Simulator),
+    ] {
+        let version = deployment_target(os, arch, abi).unwrap();
+        let (min_version, sdk_version) = versions(&pre_link_args(os, arch, abi, version).unwrap());
+        assert_eq!(min_version, version);
+        assert!(sdk_version >= min_version, "{os}: {sdk_version:?} < {min_version:?}");
+    }
+
+    // An SDK version that is pinned in the linker arguments, e.g. by
+    // `-Zapple-reproducible`, is raised to the deployment target.
+    let mut target = aarch64_apple_ios::target();
+    make_reproducible(&mut target, "1.0", true).unwrap();
+    let (min_version, sdk_version) = versions(&target.pre_link_args);
+    assert_eq!(sdk_version, min_version);
+    assert_eq!(min_version, deployment_target_for_target(&target).unwrap());
+}
+
+#[test]
+fn const_deployment_target_min() {
+    const MACOS_ARM64: Option<OSVersion> =
+        deployment_target_min("macos", Arch::Arm64, TargetAbi::Normal);
+    assert_eq!(MACOS_ARM64, Some((11, 0, 0)));
+    assert_eq!(deployment_target_min("linux", Arch::Arm64, TargetAbi::Normal), None);
+
+    let all_env_vars_unset =
+        deployment_target_env_vars().iter().all(|var| std::env::var_os(var).is_none())
+            && std::env::var_os("RUSTC_DEPLOYMENT_TARGET_FILE").is_none();
+    for (os, arch, abi) in [
+        ("macos", Arch::X86_64, TargetAbi::Normal),
+        ("macos", Arch::Arm64e, TargetAbi::Normal),
+        ("ios", Arch::Armv6, TargetAbi::Normal),
+        ("ios", Arch::Arm64, TargetAbi::MacCatalyst),
+        ("ios", Arch::X86_64, TargetAbi::MacCatalyst),
+        ("tvos", Arch::Arm64, TargetAbi::Simulator),
+        ("watchos", Arch::Arm64, TargetAbi::Normal),
+        ("visionos", Arch::Arm64, TargetAbi::Normal),
+    ] {
+        let min = deployment_target_min(os, arch, abi).unwrap();
+        assert_eq!(min_deployment_target(os, arch, abi).unwrap(), min);
+        if all_env_vars_unset {
+            assert_eq!(deployment_target(os, arch, abi).unwrap(), min);
+        }
+    }
+}
+
+#[test]
+fn platform_constant_names() {
+    assert_eq!(platform_constant_name(1), Some("PLATFORM_MACOS"));
+    assert_eq!(platform_constant_name(6), Some("PLATFORM_MACCATALYST"));
+    assert_eq!(platform_constant_name(12), Some("PLATFORM_XROSSIMULATOR"));
+    assert_eq!(platform_constant_name(0), None);
+
+    for (_, target) in builtin_apple_targets() {
+        let platform = platform(&target).unwrap();
+        assert!(platform_constant_name(platform).is_some(), "{}", target.llvm_target);
+    }
+}
+
+#[test]
+fn builtin_targets_use_the_apple_vendor() {
+    for (_, target) in builtin_apple_targets() {
+        assert_eq!(target.vendor, "apple");
+        assert_eq!(target.llvm_target.split('-').nth(1), Some("apple"), "{}", target.llvm_target);
+    }
+
+    // Custom targets may use another vendor.
+    assert_eq!(
+        deployment_target_from_llvm_target("arm64-acme-ios17.0.0-simulator"),
+        Some((17, 0, 0))
+    );
+}
+
+#[test]
+fn mac_catalyst_is_never_a_simulator() {
+    assert_eq!(TargetAbi::MacCatalyst.target_abi(), "macabi");
+    assert!(TargetAbi::from_abi_str("macabi") == Some(TargetAbi::MacCatalyst));
+    assert!(TargetAbi::from_abi_str("macabi-sim").is_none());
+
+    for &arch in Arch::all() {
+        if validate("ios", arch, TargetAbi::MacCatalyst).is_err() {
+            continue;
+        }
+        let llvm_target =
+            llvm_target("apple", "ios", arch, TargetAbi::MacCatalyst, (14, 0, 0)).unwrap();
+        assert!(llvm_target.ends_with("-macabi"), "{llvm_target}");
+        assert!(!llvm_target.contains("simulator"), "{llvm_target}");
+    }
+}
+
+#[test]
+fn deployment_target_can_be_overridden() {
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "14.1").unwrap();
+    assert_eq!(target.llvm_target, "x86_64-apple-macosx14.1.0");
+    assert_eq!(deployment_target_for_target(&target).unwrap(), (14, 1, 0));
+    let ld_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    assert!(ld_args.windows(3).any(|w| w == ["-platform_version", "macos", "14.1.0"]));
+    let cc_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, Lld::No)];
+    assert!(cc_args.iter().any(|a| a == "-mmacosx-version-min=14.1.0"));
+
+    // `-target` is updated too.
+    let mut target = aarch64_apple_ios::target();
+    override_deployment_target(&mut target, "17.0").unwrap();
+    assert_eq!(target.llvm_target, "arm64-apple-ios17.0.0");
+    let cc_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, Lld::No)];
+    assert!(cc_args.windows(2).any(|w| w == ["-target", "arm64-apple-ios17.0.0"]));
+
+    // Too low versions are raised, like those from the environment.
+    let mut target = aarch64_apple_darwin::target();
+    override_deployment_target(&mut target, "10.9").unwrap();
+    assert_eq!(target.llvm_target, "arm64-apple-macosx11.0.0");
+
+    assert_eq!(
+        override_deployment_target(&mut target, "fourteen").unwrap_err().to_string(),
+        "invalid deployment target `fourteen`"
+    );
+}
+
+#[test]
+fn apple_oses() {
+    // The OS matches in the module must all know the same OSes.
+    for os in ["macos", "ios", "tvos", "watchos", "visionos"] {
+        assert!(llvm_os_name(os).is_ok(), "{os}");
+        assert!(macho_platform(os, TargetAbi::Normal).is_some(), "{os}");
+    }
+}
+
+#[test]
+fn arm64e_cpu_is_independent_of_deployment_target() {
+    assert_eq!(Arch::Arm64e.target_cpu("ios", TargetAbi::Normal), "apple-a12");
+    assert_eq!(Arch::Arm64e.target_cpu("macos", TargetAbi::Normal), "apple-m1");
+    assert_eq!(arm64e_apple_ios::target().cpu, "apple-a12");
+
+    // iPadOS 26 still runs on A12 iPads.
+    let mut target = arm64e_apple_ios::target();
+    override_deployment_target(&mut target, "26.0").unwrap();
+    assert_eq!(target.cpu, "apple-a12");
+}
+
+#[test]
+fn thread_local_support_depends_on_deployment_target() {
+    assert!(!supports_thread_local("macos", (10, 6, 0)));
+    assert!(supports_thread_local("macos", (10, 7, 0)));
+    assert!(!supports_thread_local("ios", (7, 1, 0)));
+    assert!(supports_thread_local("ios", (8, 0, 0)));
+    assert!(supports_thread_local("watchos", (5, 0, 0)));
+
+    // armv6 devices can't run iOS 8, every other target supports them.
+    for (_, target) in builtin_apple_targets() {
+        let expected = !target.llvm_target.starts_with("armv6-");
+        assert_eq!(target.has_thread_local, expected, "{}", target.llvm_target);
+    }
+}
+
+#[test]
+fn watchos_arm64_simulator_floor() {
+    assert_eq!(
+        min_deployment_target("watchos", Arch::Arm64, TargetAbi::Simulator).unwrap(),
+        (7, 0, 0)
+    );
+    // The x86_64 simulator keeps the usual floor.
+    assert_eq!(
+        min_deployment_target("watchos", Arch::X86_64, TargetAbi::Simulator).unwrap(),
+        (5, 0, 0)
+    );
+    assert_eq!(
+        llvm_target("apple", "watchos", Arch::Arm64, TargetAbi::Simulator, (7, 0, 0)).unwrap(),
+        "arm64-apple-watchos7.0.0-simulator"
+    );
+}
+
+#[test]
+fn reproducible_mode_pins_sdk_version() {
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "11.0").unwrap();
+    make_reproducible(&mut target, "14.2", true).unwrap();
+    let ld_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    assert!(ld_args.windows(4).any(|w| w == ["-platform_version", "macos", "11.0.0", "14.2.0"]));
+    assert!(zeroes_ar_date(&target));
+
+    // The SDK version is still never lower than the deployment target.
+    let mut target = aarch64_apple_ios::target();
+    override_deployment_target(&mut target, "17.0").unwrap();
+    make_reproducible(&mut target, "16.2", true).unwrap();
+    let ld_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    assert!(ld_args.windows(4).any(|w| w == ["-platform_version", "ios", "17.0.0", "17.0.0"]));
+
+    // Zippered binaries record the matching Mac Catalyst SDK version.
+    let mut target = aarch64_apple_darwin::target();
+    zipper(&mut target).unwrap();
+    make_reproducible(&mut target, "14.2", true).unwrap();
+    let ld_args = &target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)];
+    let sdk_versions: Vec<_> = ld_args
+        .windows(4)
+        .filter(|w| w[0] == "-platform_version")
+        .map(|w| (w[1].as_ref(), w[3].as_ref()))
+        .collect();
+    assert_eq!(sdk_versions, [("macos", "14.2.0"), ("mac-catalyst", "17.2.0")]);
+
+    assert_eq!(parse_sdk_version("14.2").unwrap(), (14, 2, 0));
+    let mut target = x86_64_apple_darwin::target();
+    assert_eq!(
+        make_reproducible(&mut target, "latest", true).unwrap_err().to_string(),
+        "invalid SDK version `latest`"
+    );
+    if std::env::var_os("MACOSX_DEPLOYMENT_TARGET").is_none()
+        && std::env::var_os("RUSTC_DEPLOYMENT_TARGET_FILE").is_none()
+    {
+        assert_eq!(
+            make_reproducible(&mut target, "14.2", false).unwrap_err(),
+            AppleTargetError::ImplicitDeploymentTarget { env_var: "MACOSX_DEPLOYMENT_TARGET" }
+        );
+    }
+}
+
+#[test]
+fn arm64_simulator_cpus() {
+    let cpu = |os| Arch::Arm64.target_cpu(os, TargetAbi::Simulator);
+    assert_eq!(cpu("ios"), "apple-a12");
+    assert_eq!(cpu("tvos"), "apple-m1");
+    assert_eq!(cpu("watchos"), "apple-m1");
+    assert_eq!(cpu("visionos"), "apple-a12");
+    // The devices are unaffected.
+    assert_eq!(Arch::Arm64.target_cpu("ios", TargetAbi::Normal), "apple-a7");
+    assert_eq!(Arch::Arm64.target_cpu("tvos", TargetAbi::Normal), "apple-a7");
+
+    assert_eq!(aarch64_apple_ios_sim::target().cpu, "apple-a12");
+    assert_eq!(aarch64_apple_tvos_sim::target().cpu, "apple-m1");
+    assert_eq!(aarch64_apple_watchos_sim::target().cpu, "apple-m1");
+    assert_eq!(aarch64_apple_visionos_sim::target().cpu, "apple-a12");
+}
+
+#[test]
+fn platform_version_platform_names() {
+    let name = |os, abi| {
+        let [platform_name, ..] = platform_version(os, abi, (17, 0, 0));
+        platform_name
+    };
+    assert_eq!(name("macos", TargetAbi::Normal), "macos");
+    assert_eq!(name("ios", TargetAbi::Normal), "ios");
+    assert_eq!(name("tvos", TargetAbi::Normal), "tvos");
+    assert_eq!(name("watchos", TargetAbi::Normal), "watchos");
+    assert_eq!(name("visionos", TargetAbi::Normal), "visionos");
+    // ld64 requires the hyphenated spelling.
+    assert_eq!(name("ios", TargetAbi::MacCatalyst), "mac-catalyst");
+    assert_eq!(name("ios", TargetAbi::Simulator), "ios-simulator");
+    assert_eq!(name("tvos", TargetAbi::Simulator), "tvos-simulator");
+    assert_eq!(name("watchos", TargetAbi::Simulator), "watchos-simulator");
+    assert_eq!(name("visionos", TargetAbi::Simulator), "visionos-simulator");
+
+    // The built-in targets pass the same names.
+    let ld_args = |target: Target| -> Vec<String> {
+        target.pre_link_args[&LinkerFlavor::Darwin(Cc::No, Lld::No)]
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    };
+    let platform_name = |target| {
+        let args = ld_args(target);
+        let i = args.iter().position(|a| a == "-platform_version").unwrap();
+        args[i + 1].clone()
+    };
+    assert_eq!(platform_name(aarch64_apple_ios_macabi::target()), "mac-catalyst");
+    assert_eq!(platform_name(aarch64_apple_ios_sim::target()), "ios-simulator");
+    assert_eq!(platform_name(aarch64_apple_tvos_sim::target()), "tvos-simulator");
+    assert_eq!(platform_name(aarch64_apple_watchos_sim::target()), "watchos-simulator");
+}
+
+#[test]
+fn arm64e_enables_pointer_authentication() {
+    for (_, target) in builtin_apple_targets() {
+        let features: Vec<_> = target.features.split(',').collect();
+        let is_arm64e = target.llvm_target.starts_with("arm64e-");
+        assert_eq!(features.contains(&"+pauth"), is_arm64e, "{}", target.llvm_target);
+        if is_arm64e {
+            assert!(features.contains(&"+v8.3a"), "{}", target.llvm_target);
+        }
+    }
+}
+
+#[test]
+fn sdk_versions() {
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "12.0").unwrap();
+    assert_eq!(linked_platform_version(&target), Some(((12, 0, 0), (12, 0, 0))));
+    make_reproducible(&mut target, "14.2", true).unwrap();
+    assert_eq!(linked_platform_version(&target), Some(((12, 0, 0), (14, 2, 0))));
+
+    let settings = concat!(
+        r#"{"CanonicalName": "macosx14.2", "Version": "14.2", "#,
+        r#""MaximumDeploymentTarget": "14.2.99"}"#,
+    );
+    assert_eq!(sdk_settings_version(settings), Some((14, 2, 0)));
+    assert_eq!(sdk_settings_version(r#"{"Version": 14}"#), None);
+    assert_eq!(sdk_settings_version("not json"), None);
+
+    // Without `SDKSettings.json`, the version is taken from the SDK's name.
+    let sdk_root = |path: &str| sdk_root_version(Path::new(path));
+    assert_eq!(sdk_root("/nonexistent/MacOSX14.2.sdk"), Some((14, 2, 0)));
+    assert_eq!(sdk_root("/nonexistent/iPhoneSimulator17.0.sdk"), Some((17, 0, 0)));
+    assert_eq!(sdk_root("/nonexistent/MacOSX.sdk"), None);
+    assert_eq!(sdk_root("/nonexistent/MacOSX14.2"), None);
+}
+
+#[test]
+fn watchos_armv7k_floor() {
+    assert_eq!(
+        min_deployment_target("watchos", Arch::Armv7k, TargetAbi::Normal).unwrap(),
+        (4, 0, 0)
+    );
+    // `arm64_32` keeps the watchOS floor.
+    assert_eq!(
+        min_deployment_target("watchos", Arch::Arm64_32, TargetAbi::Normal).unwrap(),
+        (5, 0, 0)
+    );
+
+    if std::env::var_os("WATCHOS_DEPLOYMENT_TARGET").is_none()
+        && std::env::var_os("RUSTC_DEPLOYMENT_TARGET_FILE").is_none()
+    {
+        let target = armv7k_apple_watchos::target();
+        assert_eq!(target.llvm_target, "armv7k-apple-watchos4.0.0");
+        assert_eq!(deployment_target_for_target(&target).unwrap(), (4, 0, 0));
+    }
+}
+
+#[test]
+fn cc_version_min_replaces_target() {
+    let cc_args = |target: &Target| -> Vec<String> {
+        target.pre_link_args[&LinkerFlavor::Darwin(Cc::Yes, Lld::No)]
+            .iter()
+            .map(|a| a.to_string())
+            .collect()
+    };
+    let version_min = |mut target: Target| {
+        use_cc_version_min(&mut target).unwrap();
+        let args = cc_args(&target);
+        assert!(!args.iter().any(|a| a == "-target"), "{}", target.llvm_target);
+        assert_eq!(args[..2], ["-arch", "arm64"], "{}", target.llvm_target);
+        let (major, minor, patch) = deployment_target_for_target(&target).unwrap();
+        args[2].strip_suffix(&format!("={major}.{minor}.{patch}")).unwrap().to_string()
+    };
+    assert_eq!(version_min(aarch64_apple_ios::target()), "-miphoneos-version-min");
+    assert_eq!(version_min(aarch64_apple_ios_sim::target()), "-mios-simulator-version-min");
+    assert_eq!(version_min(aarch64_apple_tvos_sim::target()), "-mtvos-simulator-version-min");
+    assert_eq!(version_min(aarch64_apple_watchos_sim::target()), "-mwatchos-simulator-version-min");
+
+    // `-target` stays the default.
+    assert!(cc_args(&aarch64_apple_ios::target()).iter().any(|a| a == "-target"));
+}
+
+#[test]
+fn mac_catalyst_has_no_cc_version_min() {
+    let err = use_cc_version_min(&mut aarch64_apple_ios_macabi::target()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Mac Catalyst can only be selected with `-target` when linking through `cc`"
+    );
+    let err = use_cc_version_min(&mut aarch64_apple_visionos_sim::target()).unwrap_err();
+    assert_eq!(err, AppleTargetError::NoCcVersionMin { os: "visionos".into(), abi: "sim" });
+}
+
+#[test]
+fn arch_from_rust_triples() {
+    assert!(arch_from_rust_triple("arm64e-apple-ios") == Some(Arch::Arm64e));
+    assert!(arch_from_rust_triple("aarch64-apple-darwin") == Some(Arch::Arm64));
+    assert!(arch_from_rust_triple("arm64-apple-macosx11.0.0") == Some(Arch::Arm64));
+    assert!(arch_from_rust_triple("arm64_32-apple-watchos") == Some(Arch::Arm64_32));
+    assert!(arch_from_rust_triple("x86_64h-apple-darwin") == Some(Arch::X86_64h));
+    assert!(arch_from_rust_triple("x86_64-apple-ios-macabi") == Some(Arch::X86_64));
+    assert!(arch_from_rust_triple("armv7k-apple-watchos") == Some(Arch::Armv7k));
+    assert!(arch_from_rust_triple("i386-apple-ios") == Some(Arch::I386));
+    assert!(arch_from_rust_triple("riscv64gc-unknown-linux-gnu").is_none());
+    assert!(arch_from_rust_triple("arm64e").is_none());
+
+    // The Rust triple and the LLVM target of every built-in target agree.
+    for (triple, target) in builtin_apple_targets() {
+        let arch = arch_from_rust_triple(triple);
+        assert!(arch.is_some(), "{triple}");
+        assert!(arch == arch_from_rust_triple(&target.llvm_target), "{triple}");
+        assert_eq!(arch.unwrap().target_arch(), target.arch, "{triple}");
+    }
+
+    // Custom targets still get a reasonable architecture.
+    let mut target = x86_64_apple_darwin::target();
+    target.llvm_target = "x86_64-apple-macosx".into();
+    assert!(target_arch_and_abi(&target).unwrap().0 == Arch::X86_64);
+    let mut target = armv7k_apple_watchos::target();
+    target.llvm_target = "thumbv7k-apple-watchos".into();
+    assert!(target_arch_and_abi(&target).unwrap().0 == Arch::X86_64);
+    target.llvm_target = "armv7k-apple-watchos".into();
+    assert!(target_arch_and_abi(&target).unwrap().0 == Arch::Armv7k);
+}
+
+#[test]
+fn strict_sdk_version() {
+    let mut target = x86_64_apple_darwin::target();
+    override_deployment_target(&mut target, "11.0").unwrap();
+    assert_eq!(
+        require_sdk_version(&mut target, Some("/nonexistent/MacOSX14.2.sdk")),
+        Ok((14, 2, 0))
+    );
+    assert_eq!(linked_platform_version(&target), Some(((11, 0, 0), (14, 2, 0))));
+
+    // The SDK version is still never lower than the deployment target.
+    let mut target = aarch64_apple_ios::target();
+    override_deployment_target(&mut target, "17.0").unwrap();
+    require_sdk_version(&mut target, Some("/nonexistent/iPhoneOS16.2.sdk")).unwrap();
+    assert_eq!(linked_platform_version(&target), Some(((17, 0, 0), (17, 0, 0))));
+
+    let mut target = x86_64_apple_darwin::target();
+    assert_eq!(
+        require_sdk_version(&mut target, None).unwrap_err(),
+        AppleTargetError::UnknownSdkVersion { sdk_root: None }
+    );
+    assert_eq!(
+        require_sdk_version(&mut target, Some("/nonexistent/MacOSX.sdk")).unwrap_err().to_string(),
+        "cannot determine the version of the SDK at `/nonexistent/MacOSX.sdk`"
+    );
+    // Like when linking, `SDKROOT` is ignored if it's for another platform.
+    let sdk_root = "/Applications/Xcode.app/Contents/Developer/Platforms/iPhoneOS.platform/\
+                    Developer/SDKs/iPhoneOS17.0.sdk";
+    assert_eq!(
+        require_sdk_version(&mut target, Some(sdk_root)).unwrap_err(),
+        AppleTargetError::UnknownSdkVersion { sdk_root: None }
+    );
+    let mut target = aarch64_apple_ios::target();
+    assert!(require_sdk_version(&mut target, Some(sdk_root)).is_ok());
+    let sdk_root = "/Applications/Xcode.app/Contents/Developer/Platforms/MacOSX.platform/\
+                    Developer/SDKs/MacOSX14.2.sdk";
+    assert!(require_sdk_version(&mut target, Some(sdk_root)).is_err());
+    // Mac Catalyst uses the macOS SDK.
+    let mut target = aarch64_apple_ios_macabi::target();
+    assert!(require_sdk_version(&mut target, Some(sdk_root)).is_ok());
+}
+
+#[test]
+fn abi_return_struct_as_int() {
+    for (triple, target) in builtin_apple_targets() {
+        assert!(target.abi_return_struct_as_int, "{triple}");
+    }
+
+    let (opts, ..) =
+        AppleTargetBuilder::new("ios", Arch::I386, TargetAbi::Simulator).unwrap().build();
+    assert!(opts.abi_return_struct_as_int);
+}
+
+/// Set in the child processes of `run_in_child`.
+const CHILD_PROCESS: &str = "RUSTC_APPLE_TEST_CHILD_PROCESS";
+
+/// Run the test `name` (in this module) again in a child process with the
+/// environment variables `vars` set, as setting them in this process would
+/// affect the tests that run concurrently. Returns whether this is the child.
+fn run_in_child(name: &str, vars: &[(&str, &str)]) -> bool {
+    if std::env::var_os(CHILD_PROCESS).is_some() {
+        return true;
+    }
+    let mut cmd = std::process::Command::new(std::env::current_exe().unwrap());
+    cmd.args(["--exact", &format!("spec::base::apple::tests::{name}"), "--test-threads=1"]);
+    cmd.env(CHILD_PROCESS, "1").env_remove("RUSTC_DEPLOYMENT_TARGET_FILE");
+    cmd.envs(vars.iter().copied());
+    let output = cmd.output().unwrap();
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "with {vars:?}:\n{stdout}");
+    assert!(stdout.contains("1 passed"), "with {vars:?}:\n{stdout}");
+    false
+}
+
+#[test]
+fn llvm_target_matches_deployment_target() {
+    // This holds in whatever environment the tests are run in.
+    for &(os, ..) in DEPLOYMENT_TARGETS {
+        for &arch in Arch::all() {
+            for abi in [TargetAbi::Normal, TargetAbi::Simulator, TargetAbi::MacCatalyst] {
+                if validate(os, arch, abi).is_err() {
+                    continue;
+                }
+                let version = deployment_target(os, arch, abi).unwrap();
+                let (_, llvm_target, _) = base(os, arch, abi).unwrap();
+                let context = format!("{os} {} {abi}", arch.target_name());
+                assert_eq!(
+                    deployment_target_from_llvm_target(&llvm_target),
+                    Some(version),
+                    "{context}"
+                );
+                let (_, llvm_target, _) = AppleTargetBuilder::new(os, arch, abi).unwrap().build();
+                assert_eq!(
+                    deployment_target_from_llvm_target(&llvm_target),
+                    Some(version),
+                    "{context}"
+                );
+            }
+        }
+    }
+
+    // Too low for all OSes, in between, too high, and invalid.
+    for version in ["1.0", "13.2", "17.0.1", "99.0", "latest"] {
+        let vars: Vec<_> = deployment_target_env_vars().iter().map(|&var| (var, version)).collect();
+        if run_in_child("llvm_target_matches_deployment_target", &vars) {
+            return;
+        }
+    }
+}
+
+#[test]
+fn source_date_epoch_sdk_version() {
+    if std::env::var_os("SOURCE_DATE_EPOCH").is_none() {
+        // The SDK versions built into `rustc`.
+        assert_eq!(
+            object_sdk_version(object::macho::PLATFORM_MACOS, (10, 12, 0)),
+            Some((13, 1, 0))
+        );
+        assert_eq!(object_sdk_version(object::macho::PLATFORM_IOS, (17, 0, 0)), Some((17, 0, 0)));
+        let vars = [("SOURCE_DATE_EPOCH", "0"), ("SDKROOT", "/nonexistent/MacOSX99.0.sdk")];
+        run_in_child("source_date_epoch_sdk_version", &vars);
+        return;
+    }
+
+    // Nothing from the build machine (or `rustc`'s built-in versions) leaks
+    // into the SDK version.
+    assert_eq!(object_sdk_version(object::macho::PLATFORM_MACOS, (10, 12, 0)), Some((10, 12, 0)));
+    assert_eq!(object_sdk_version(object::macho::PLATFORM_IOS, (17, 0, 0)), Some((17, 0, 0)));
+    assert_eq!(object_sdk_version(0, (10, 12, 0)), None);
+    for (triple, target) in builtin_apple_targets() {
+        let (min_version, sdk_version) = linked_platform_version(&target).unwrap();
+        assert_eq!(sdk_version, min_version, "{triple}");
+        let (arch, abi) = target_arch_and_abi(&target).unwrap();
+        let &(os, ..) = DEPLOYMENT_TARGETS.iter().find(|(os, ..)| *os == target.os).unwrap();
+        let info = resolve(os, arch, abi).unwrap();
+        assert_eq!(info.sdk_version, info.deployment_target, "{triple}");
+    }
+}
+
+#[test]
+fn native_deployment_targets() {
+    let plist = r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN"
+    "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>ProductBuildVersion</key>
+	<string>23C71</string>
+	<key>ProductName</key>
+	<string>macOS</string>
+	<key>ProductVersion</key>
+	<string>14.2.1</string>
+</dict>
+</plist>"#;
+    assert_eq!(plist_product_version(plist), Some((14, 2, 1)));
+    assert_eq!(plist_product_version("<key>ProductVersion</key><integer>14</integer>"), None);
+    assert_eq!(plist_product_version(""), None);
+
+    let mut target = aarch64_apple_ios::target();
+    assert_eq!(
+        override_deployment_target(&mut target, "native"),
+        Err(AppleTargetError::NativeDeploymentTargetUnsupported)
+    );
+    // Only macOS hosts can query their version.
+    let mut target = aarch64_apple_darwin::target();
+    if !cfg!(target_os = "macos") {
+        assert_eq!(
+            override_deployment_target(&mut target, "native"),
+            Err(AppleTargetError::NativeDeploymentTargetUnsupported)
+        );
+    } else if std::env::var_os("MACOSX_DEPLOYMENT_TARGET").is_none()
+        && std::env::var_os("RUSTC_DEPLOYMENT_TARGET_FILE").is_none()
+    {
+        let version = host_macos_version().unwrap();
+        override_deployment_target(&mut target, "native").unwrap();
+        assert_eq!(deployment_target_for_target(&target).unwrap(), version.max((11, 0, 0)));
+    }
+}
+
+#[test]
+fn supported_split_debuginfo_values() {
+    let all = [SplitDebuginfo::Packed, SplitDebuginfo::Unpacked, SplitDebuginfo::Off];
+    for (triple, target) in builtin_apple_targets() {
+        assert_eq!(*target.supported_split_debuginfo, all, "{triple}");
+    }
+}
+
+#[test]
+fn universal_binary_deployment_targets() {
+    let settings = "\
+MACOSX_DEPLOYMENT_TARGET[arch=x86_64] = 10.13
+MACOSX_DEPLOYMENT_TARGET = 11.0
+MACOSX_DEPLOYMENT_TARGET[arch=arm64e] = 12.3
+MACOSX_DEPLOYMENT_TARGET_SUFFIXED = 9.0
+";
+    let setting = |arch| build_setting(settings, "MACOSX_DEPLOYMENT_TARGET", "macosx", arch);
+    assert_eq!(setting("x86_64"), Some("10.13"));
+    assert_eq!(setting("arm64"), Some("11.0"));
+    assert_eq!(setting("arm64e"), Some("12.3"));
+    assert_eq!(setting("x86_64h"), Some("11.0"));
+    assert_eq!(build_setting("KEY[arch=x86_64] = 1", "KEY", "macosx", "arm64"), None);
+    assert_eq!(build_setting("KEY = [a]=b", "KEY", "macosx", "arm64"), Some("[a]=b"));
+
+    let settings = "\
+KEY = 1
+KEY[sdk=macosx*] = 2
+KEY[sdk=macosx*][arch=arm64] = 3
+KEY[arch=x86_64][sdk=iphonesimulator*] = 4
+KEY[config=Debug] = 5
+KEY[sdk=macosx*][config=Debug] = 6
+KEY[arch=arm64 = 7
+";
+    let setting = |sdk, arch| build_setting(settings, "KEY", sdk, arch);
+    assert_eq!(setting("macosx", "arm64"), Some("3"));
+    assert_eq!(setting("macosx", "x86_64"), Some("2"));
+    assert_eq!(setting("iphonesimulator", "x86_64"), Some("4"));
+    assert_eq!(setting("iphonesimulator", "arm64"), Some("1"));
+    assert_eq!(setting("iphoneos", "arm64"), Some("1"));
+    assert_eq!(build_setting("KEY[arch=arm64e*] = 1", "KEY", "macosx", "arm64"), None);
+
+    // The slices of a universal binary don't affect each other, the ARM64
+    // slice is still raised to 11.0 independently of the x86_64 slice.
+    let path = std::env::temp_dir().join(format!("rustc-apple-{}.xcconfig", std::process::id()));
+    if std::env::var_os(CHILD_PROCESS).is_none() {
+        std::fs::write(&path, "MACOSX_DEPLOYMENT_TARGET = 10.13\n").unwrap();
+    }
+    let child = run_in_child(
+        "universal_binary_deployment_targets",
+        &[("RUSTC_DEPLOYMENT_TARGET_FILE", path.to_str().unwrap())],
+    );
+    if !child {
+        std::fs::remove_file(&path).unwrap();
+        return;
+    }
+    assert_eq!(check_deployment_target_file(), Ok(()));
+    assert_eq!(deployment_target("macos", Arch::X86_64, TargetAbi::Normal), Ok((10, 13, 0)));
+    assert_eq!(deployment_target("macos", Arch::Arm64, TargetAbi::Normal), Ok((11, 0, 0)));
+    assert_eq!(x86_64_apple_darwin::target().llvm_target, "x86_64-apple-macosx10.13.0");
+    assert_eq!(aarch64_apple_darwin::target().llvm_target, "arm64-apple-macosx11.0.0");
+}
+
+#[test]
+fn unreadable_deployment_target_file() {
+    let path = std::env::temp_dir().join("rustc-apple-missing.xcconfig");
+    let path = path.to_str().unwrap();
+    let vars = [(DEPLOYMENT_TARGET_FILE, path), ("MACOSX_DEPLOYMENT_TARGET", "10.13")];
+    if !run_in_child("unreadable_deployment_target_file", &vars) {
+        return;
+    }
+    let err = check_deployment_target_file().unwrap_err();
+    let AppleTargetError::UnreadableDeploymentTargetFile { path: err_path, .. } = &err else {
+        panic!("{err:?}");
+    };
+    assert_eq!(*err_path, path);
+    let message = err.to_string();
+    let prefix =
+        format!("cannot read the build settings at `{path}` from `{DEPLOYMENT_TARGET_FILE}`: ");
+    assert!(message.starts_with(&prefix), "{message}");
+
+    // Without the file, the deployment target still comes from the environment.
+    assert_eq!(deployment_target("macos", Arch::X86_64, TargetAbi::Normal), Ok((10, 13, 0)));
+}
+
+#[test]
+fn requested_deployment_target_with_leading_equals() {
+    assert_eq!(parse_requested_deployment_target("12.0"), Some((12, 0, 0)));
+    assert_eq!(parse_requested_deployment_target("=12.0"), Some((12, 0, 0)));
+    assert_eq!(parse_requested_deployment_target("==12.0"), None);
+    assert_eq!(parse_requested_deployment_target("="), None);
+    assert_eq!(parse_requested_deployment_target("12.0="), None);
+    assert_eq!(parse_requested_deployment_target("=twelve"), None);
+    // `-Zapple-deployment-target` is strict.
+    assert!(parse_version("=12.0").is_err());
+
+    if run_in_child(
+        "requested_deployment_target_with_leading_equals",
+        &[("MACOSX_DEPLOYMENT_TARGET", "=12.0")],
+    ) {
+        assert_eq!(deployment_target("macos", Arch::X86_64, TargetAbi::Normal), Ok((12, 0, 0)));
+    }
+}
+
+#[test]
+fn watchos_device_and_simulator_deployment_targets() {
+    let min = |arch, abi| min_deployment_target("watchos", arch, abi).unwrap();
+    assert_eq!(min(Arch::Arm64_32, TargetAbi::Normal), (5, 0, 0));
+    assert_eq!(min(Arch::Armv7k, TargetAbi::Normal), (4, 0, 0));
+    assert_eq!(min(Arch::Arm64, TargetAbi::Normal), (5, 0, 0));
+    assert_eq!(min(Arch::Arm64, TargetAbi::Simulator), (7, 0, 0));
+    assert_eq!(min(Arch::X86_64, TargetAbi::Simulator), (5, 0, 0));
+
+    // There is no `arm64_32` (or `armv7k`) simulator, tests for those devices
+    // run on the `arm64` (or `x86_64`) simulator instead.
+    for arch in [Arch::Arm64_32, Arch::Armv7k] {
+        assert_eq!(
+            validate("watchos", arch, TargetAbi::Simulator),
+            Err(AppleTargetError::DeviceOnlyArch {
+                os: "watchos".into(),
+                arch: arch.target_name()
+            })
+        );
+        assert!(base("watchos", arch, TargetAbi::Simulator).is_err());
+    }
+    assert_eq!(
+        validate("ios", Arch::Armv7s, TargetAbi::Simulator).unwrap_err().to_string(),
+        "the `armv7s` architecture is not supported in the ios simulator"
+    );
+
+    if std::env::var_os("WATCHOS_DEPLOYMENT_TARGET").is_none()
+        && std::env::var_os("RUSTC_DEPLOYMENT_TARGET_FILE").is_none()
+    {
+        let resolved = |target: Target| deployment_target_for_target(&target).unwrap();
+        assert_eq!(resolved(arm64_32_apple_watchos::target()), (5, 0, 0));
+        assert_eq!(resolved(armv7k_apple_watchos::target()), (4, 0, 0));
+        assert_eq!(resolved(aarch64_apple_watchos::target()), (5, 0, 0));
+        assert_eq!(resolved(aarch64_apple_watchos_sim::target()), (7, 0, 0));
+        assert_eq!(resolved(x86_64_apple_watchos_sim::target()), (5, 0, 0));
+    }
 }
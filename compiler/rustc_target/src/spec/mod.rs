@@ -60,8 +60,32 @@
 
 mod base;
 pub use base::apple::{
+    arch_from_rust_triple as apple_arch_from_rust_triple,
+    check_deployment_target_file as check_apple_deployment_target_file,
+    dead_strip_dylibs as dead_strip_apple_dylibs,
+    deployment_target_env_vars as apple_deployment_target_env_vars,
+    deployment_target_file_path as apple_deployment_target_file_path,
     deployment_target_for_target as current_apple_deployment_target,
-    platform as current_apple_platform, sdk_version as current_apple_sdk_version,
+    disable_adhoc_codesign as disable_apple_adhoc_codesign,
+    emit_bitcode_marker as emit_apple_bitcode_marker, keep_ar_date as keep_apple_ar_date,
+    ld_arch_is_downgraded as current_apple_ld_arch_is_downgraded,
+    link_as_application_extension as link_apple_target_as_application_extension,
+    linked_platform_version as current_apple_linked_platform_version,
+    make_reproducible as make_apple_target_reproducible,
+    min_deployment_target_for_target as current_apple_min_deployment_target,
+    object_sdk_version as apple_object_sdk_version,
+    override_deployment_target as override_apple_deployment_target,
+    parse_sdk_version as parse_apple_sdk_version, platform as current_apple_platform,
+    platform_constant_name as apple_platform_constant_name,
+    require_sdk_version as require_apple_sdk_version,
+    sdk_platform_name as current_apple_sdk_platform_name,
+    sdk_root_version as apple_sdk_root_version, sdk_version as current_apple_sdk_version,
+    sdkroot_other_platform as apple_sdkroot_other_platform, use_cc_target as use_apple_cc_target,
+    use_cc_version_min as use_apple_cc_version_min,
+    use_legacy_version_min as use_apple_legacy_version_min,
+    zero_sdk_version as zero_apple_sdk_version, zipper as zipper_apple_target, AppleTargetError,
+    Arch as AppleArch, OSVersion, DEPLOYMENT_TARGET_FILE as APPLE_DEPLOYMENT_TARGET_FILE,
+    KEEP_SDKROOT as APPLE_KEEP_SDKROOT,
 };
 pub use base::avr_gnu::ef_avr_arch;
 
@@ -1710,6 +1734,7 @@ fn $module() {
     ("x86_64-apple-ios", x86_64_apple_ios),
     ("aarch64-apple-ios", aarch64_apple_ios),
     ("arm64e-apple-ios", arm64e_apple_ios),
+    ("armv6-apple-ios", armv6_apple_ios),
     ("armv7s-apple-ios", armv7s_apple_ios),
     ("x86_64-apple-ios-macabi", x86_64_apple_ios_macabi),
     ("aarch64-apple-ios-macabi", aarch64_apple_ios_macabi),
@@ -2430,6 +2455,10 @@ pub struct TargetOptions {
     /// Whether the target supports XRay instrumentation.
     pub supports_xray: bool,
 
+    /// The highest deployment target that may be used with this Apple target,
+    /// e.g. `"14.0"`. Higher deployment targets are rejected.
+    pub apple_max_deployment_target: Option<StaticCow<str>>,
+
     /// Whether the targets supports -Z small-data-threshold
     small_data_threshold_support: SmallDataThresholdSupport,
 }
@@ -2649,6 +2678,7 @@ fn default() -> TargetOptions {
             entry_name: "main".into(),
             entry_abi: Conv::C,
             supports_xray: false,
+            apple_max_deployment_target: None,
             small_data_threshold_support: SmallDataThresholdSupport::DefaultForArch,
         }
     }
@@ -3375,6 +3405,7 @@ macro_rules! key {
         key!(entry_name);
         key!(entry_abi, Conv)?;
         key!(supports_xray, bool);
+        key!(apple_max_deployment_target, optional);
 
         if base.is_builtin {
             // This can cause unfortunate ICEs later down the line.
@@ -3656,6 +3687,7 @@ macro_rules! target_option_val {
         target_option_val!(entry_name);
         target_option_val!(entry_abi);
         target_option_val!(supports_xray);
+        target_option_val!(apple_max_deployment_target);
 
         // Serializing `-Clink-self-contained` needs a dynamic key to support the
         // backwards-compatible variants.
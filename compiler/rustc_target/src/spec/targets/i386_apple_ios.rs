@@ -4,7 +4,7 @@
 pub(crate) fn target() -> Target {
     // i386-apple-ios is a simulator target, even though it isn't declared
     // that way in the target name like the other ones...
-    let (opts, llvm_target, arch) = base("ios", Arch::I386, TargetAbi::Simulator);
+    let (opts, llvm_target, arch) = base("ios", Arch::I386, TargetAbi::Simulator).unwrap();
     Target {
         llvm_target,
         metadata: crate::spec::TargetMetadata {
@@ -1,24 +1,29 @@
-use crate::spec::base::apple::{base, Arch, TargetAbi};
-use crate::spec::{FramePointer, SanitizerSet, Target, TargetOptions};
+use crate::spec::base::apple::{AppleTargetBuilder, Arch, TargetAbi};
+use crate::spec::{SanitizerSet, Target, TargetOptions};
 
 pub(crate) fn target() -> Target {
-    let (mut opts, llvm_target, arch) = base("macos", Arch::X86_64h, TargetAbi::Normal);
-    opts.max_atomic_width = Some(128);
-    opts.frame_pointer = FramePointer::Always;
-    opts.supported_sanitizers =
-        SanitizerSet::ADDRESS | SanitizerSet::CFI | SanitizerSet::LEAK | SanitizerSet::THREAD;
-
-    // x86_64h is core2-avx without a few of the features which would otherwise
-    // be guaranteed, so we need to disable those. This imitates clang's logic:
-    // - https://github.com/llvm/llvm-project/blob/bd1f7c417/clang/lib/Driver/ToolChains/Arch/X86.cpp#L77-L78
-    // - https://github.com/llvm/llvm-project/blob/bd1f7c417/clang/lib/Driver/ToolChains/Arch/X86.cpp#L133-L141
-    //
-    // FIXME: Sadly, turning these off here disables them in such a way that they
-    // aren't re-enabled by `-Ctarget-cpu=native` (on a machine that has them).
-    // It would be nice if this were not the case, but fixing it seems tricky
-    // (and given that the main use-case for this target is for use in universal
-    // binaries, probably not that important).
-    opts.features = "-rdrnd,-aes,-pclmul,-rtm,-fsgsbase".into();
+    let (opts, llvm_target, arch) =
+        AppleTargetBuilder::new("macos", Arch::X86_64h, TargetAbi::Normal)
+            .unwrap()
+            .max_atomic_width(128)
+            .supported_sanitizers(
+                SanitizerSet::ADDRESS
+                    | SanitizerSet::CFI
+                    | SanitizerSet::LEAK
+                    | SanitizerSet::THREAD,
+            )
+            // x86_64h is core2-avx without a few of the features which would otherwise
+            // be guaranteed, so we need to disable those. This imitates clang's logic:
+            // - https://github.com/llvm/llvm-project/blob/bd1f7c417/clang/lib/Driver/ToolChains/Arch/X86.cpp#L77-L78
+            // - https://github.com/llvm/llvm-project/blob/bd1f7c417/clang/lib/Driver/ToolChains/Arch/X86.cpp#L133-L141
+            //
+            // FIXME: Sadly, turning these off here disables them in such a way that they
+            // aren't re-enabled by `-Ctarget-cpu=native` (on a machine that has them).
+            // It would be nice if this were not the case, but fixing it seems tricky
+            // (and given that the main use-case for this target is for use in universal
+            // binaries, probably not that important).
+            .features("-rdrnd,-aes,-pclmul,-rtm,-fsgsbase")
+            .build();
     // Double-check that the `cpu` is what we expect (if it's not the list above
     // may need updating).
     assert_eq!(
@@ -19,6 +19,11 @@ fn check_consistency(&self) {
         if self.is_like_msvc {
             assert!(self.is_like_windows);
         }
+        if self.is_like_osx {
+            // Apple's ABIs require frame pointers.
+            assert_ne!(self.frame_pointer, FramePointer::MayOmit);
+        }
+        assert!(self.supported_split_debuginfo.contains(&self.split_debuginfo));
 
         // Check that default linker flavor is compatible with some other key properties.
         assert_eq!(self.is_like_osx, matches!(self.linker_flavor, LinkerFlavor::Darwin(..)));
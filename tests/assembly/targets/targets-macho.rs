@@ -41,6 +41,9 @@
 //@ revisions: arm64e_apple_ios
 //@ [arm64e_apple_ios] compile-flags: --target arm64e-apple-ios
 //@ [arm64e_apple_ios] needs-llvm-components: aarch64
+//@ revisions: armv6_apple_ios
+//@ [armv6_apple_ios] compile-flags: --target armv6-apple-ios
+//@ [armv6_apple_ios] needs-llvm-components: arm
 //@ revisions: armv7k_apple_watchos
 //@ [armv7k_apple_watchos] compile-flags: --target armv7k-apple-watchos
 //@ [armv7k_apple_watchos] needs-llvm-components: arm
@@ -55,17 +55,43 @@ fn main() {
         rustc().env(env_var, example_version).run();
         minos("foo.o", example_version);
 
-        // FIXME(madsmtm): Doesn't work on Mac Catalyst and the simulator.
-        if !target().contains("macabi") && !target().contains("sim") {
+        // FIXME(madsmtm): Doesn't work on the iOS and visionOS simulators.
+        if !target().contains("sim") || apple_os() == "tvos" || apple_os() == "watchos" {
             rustc().env_remove(env_var).run();
             minos("foo.o", default_version);
         }
+
+        // The tvOS and watchOS simulators must embed the requested version too.
+        if (apple_os() == "tvos" || apple_os() == "watchos") && target().contains("sim") {
+            rustc().env(env_var, higher_example_version).run();
+            minos("foo.o", higher_example_version);
+        }
+    });
+
+    // Test that version makes it to the crate metadata, and back out again.
+    run_in_tmpdir(|| {
+        rustc()
+            .target(target())
+            .crate_type("rlib")
+            .input("foo.rs")
+            .env(env_var, higher_example_version)
+            .run();
+        rustc()
+            .target(target())
+            .arg("-Zls=root")
+            .input("libfoo.rlib")
+            .run()
+            .assert_stdout_contains(format!("apple_deployment_target {higher_example_version}.0"));
     });
 
     // Test that version makes it to the linker when linking dylibs.
     run_in_tmpdir(|| {
-        // Certain watchOS targets don't support dynamic linking, so we disable the test on those.
-        if apple_os() == "watchos" {
+        // The older watchOS device targets don't support dynamic linking by
+        // default, so we disable the test on those.
+        if apple_os() == "watchos"
+            && !target().contains("sim")
+            && target() != "aarch64-apple-watchos"
+        {
             return;
         }
 
@@ -104,8 +130,9 @@ fn main() {
             rustc
         };
 
-        // FIXME(madsmtm): Doesn't work on watchOS for some reason?
-        if !target().contains("watchos") {
+        // FIXME(madsmtm): Doesn't work on the older watchOS device targets
+        // for some reason?
+        if !matches!(&*target(), "arm64_32-apple-watchos" | "armv7k-apple-watchos") {
             rustc().env(env_var, example_version).run();
             minos("foo", example_version);
 
@@ -113,6 +140,12 @@ fn main() {
             minos("foo", default_version);
         }
 
+        // The watchOS simulator binary must carry the requested version.
+        if apple_os() == "watchos" && target().contains("sim") {
+            rustc().env(env_var, higher_example_version).run();
+            minos("foo", higher_example_version);
+        }
+
         // Test with ld64 instead
 
         rustc().arg("-Clinker-flavor=ld").env(env_var, example_version).run();
@@ -146,8 +179,8 @@ fn main() {
         rustc().env(env_var, higher_example_version).run();
         minos("foo.o", higher_example_version);
 
-        // FIXME(madsmtm): Doesn't work on Mac Catalyst and the simulator.
-        if !target().contains("macabi") && !target().contains("sim") {
+        // FIXME(madsmtm): Doesn't work on the simulator.
+        if !target().contains("sim") || apple_os() == "tvos" {
             rustc().env_remove(env_var).run();
             minos("foo.o", default_version);
         }
@@ -0,0 +1,4 @@
+//@ compile-flags: --target aarch64-apple-ios-macabi -Zapple-cc-version-min
+//@ needs-llvm-components: aarch64
+
+fn main() {}
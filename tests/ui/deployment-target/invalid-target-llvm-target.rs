@@ -0,0 +1,4 @@
+//@ compile-flags: --target x86_64-unknown-linux-gnu -Zunstable-options --print apple-llvm-target
+//@ needs-llvm-components: x86
+
+fn main() {}
@@ -0,0 +1,5 @@
+//@ compile-flags: --target x86_64-unknown-linux-gnu
+//@ compile-flags: -Zunstable-options --print apple-sdk-platform-path-hint
+//@ needs-llvm-components: x86
+
+fn main() {}
@@ -0,0 +1,7 @@
+//@ only-macos
+//@ compile-flags: -Zunstable-options --print apple-llvm-target
+//@ rustc-env:MACOSX_DEPLOYMENT_TARGET=14.1
+//@ normalize-stdout-test: "^[a-z0-9_]+-" -> "$$ARCH-"
+//@ check-pass
+
+fn main() {}
@@ -0,0 +1,5 @@
+//@ only-macos
+//@ compile-flags: -Zunstable-options --print apple-platform
+//@ check-pass
+
+fn main() {}
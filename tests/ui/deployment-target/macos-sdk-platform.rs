@@ -0,0 +1,5 @@
+//@ only-macos
+//@ compile-flags: -Zunstable-options --print apple-sdk-platform-path-hint
+//@ check-pass
+
+fn main() {}
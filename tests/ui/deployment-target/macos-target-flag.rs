@@ -0,0 +1,6 @@
+//@ only-macos
+//@ compile-flags: -Zapple-deployment-target=14.1 --print deployment-target
+//@ rustc-env:MACOSX_DEPLOYMENT_TARGET=12.0
+//@ check-pass
+
+fn main() {}
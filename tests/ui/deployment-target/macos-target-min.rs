@@ -0,0 +1,6 @@
+//@ only-apple
+//@ compile-flags: -Zunstable-options --print deployment-target-min
+//@ normalize-stdout-test: "\d+\.\d+\.\d+" -> "$$MIN_VERSION"
+//@ check-pass
+
+fn main() {}
@@ -0,0 +1,4 @@
+//@ compile-flags: --target x86_64-unknown-linux-gnu -Zapple-no-adhoc-codesign
+//@ needs-llvm-components: x86
+
+fn main() {}
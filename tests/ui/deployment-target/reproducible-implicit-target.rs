@@ -0,0 +1,5 @@
+//@ only-macos
+//@ compile-flags: -Zapple-reproducible=14.2
+//@ unset-rustc-env:MACOSX_DEPLOYMENT_TARGET
+
+fn main() {}
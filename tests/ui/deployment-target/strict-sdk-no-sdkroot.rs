@@ -0,0 +1,5 @@
+//@ only-macos
+//@ compile-flags: -Zapple-strict-sdk
+//@ unset-rustc-env:SDKROOT
+
+fn main() {}
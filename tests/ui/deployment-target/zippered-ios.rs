@@ -0,0 +1,4 @@
+//@ compile-flags: --target aarch64-apple-ios -Zapple-zippered
+//@ needs-llvm-components: aarch64
+
+fn main() {}
@@ -0,0 +1,3 @@
+//@ compile-flags: --print=apple-llvm-target
+
+fn main() {}
@@ -0,0 +1,3 @@
+//@ compile-flags: --print=apple-platform
+
+fn main() {}
@@ -0,0 +1,3 @@
+//@ compile-flags: --print=apple-sdk-platform-path-hint
+
+fn main() {}
@@ -0,0 +1,3 @@
+//@ compile-flags: --print=deployment-target-min
+
+fn main() {}